@@ -1,11 +1,11 @@
-use chrono::{DateTime, TimeDelta, Utc};
+use chrono::Utc;
 use futures_util::future::join_all;
-use std::ops::{Sub};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::Semaphore;
 use tokio::time::Instant;
 use trade_locker_connector::brand::api_client::{BrandApiClient, BrandApiConfig};
+use trade_locker_connector::brand::report_range::ReportDateRange;
 use trade_locker_connector::brand::{
     AccountOperationRequest, AccountStatus, CancelOrderRequest, CheckEmailRequest,
     CloseAccountPositionsRequest, CreateAccountRequest, CreateUserRequest, CreditAccountRequest,
@@ -14,7 +14,7 @@ use trade_locker_connector::brand::{
     GetTradesReportRequest, MonthlyActiveAccountsRequest, SetUserPasswordRequest,
     UpdateAccountStatusRequest,
 };
-use trade_locker_connector::models::AccountType;
+use trade_locker_connector::models::{AccountId, AccountType, GroupId, OrderId, UserId};
 
 #[tokio::main]
 async fn main() {
@@ -55,13 +55,13 @@ async fn main() {
     println!("elapsed time: {:?}", instant.elapsed());
 }
 
-pub fn get_user_id() -> String {
-    //"e1ae0e5a-863e-41f2-889f-a2194f3561b5".to_string() // prod
-    "226ada22-8ab4-42f4-a03a-764020f530d3".to_string() // dev
+pub fn get_user_id() -> UserId {
+    //UserId::new("e1ae0e5a-863e-41f2-889f-a2194f3561b5") // prod
+    UserId::new("226ada22-8ab4-42f4-a03a-764020f530d3") // dev
 }
 
-pub fn get_account_id() -> String {
-    "L#705322".to_string()
+pub fn get_account_id() -> AccountId {
+    AccountId::new("L#705322")
     //"L#705611".to_string()
     //"L#705618".to_string()
     //"L#705519".to_string()
@@ -77,9 +77,9 @@ pub fn get_email() -> String {
     "trade-locker-test123@mailinator.com".to_string()
 }
 
-pub fn get_group_id() -> Option<String> {
-    //Some("829256".to_string()) // prod PRO365-50K-1STEP
-    Some("709605".to_string()) // dev
+pub fn get_group_id() -> Option<GroupId> {
+    //Some(GroupId::new("829256")) // prod PRO365-50K-1STEP
+    Some(GroupId::new("709605")) // dev
 }
 
 pub fn get_idempotency_key() -> String {
@@ -131,7 +131,7 @@ pub async fn credit_account(rest_client: &BrandApiClient<ExampleBrandApiConfig>)
     let resp = rest_client
         .credit_account(&CreditAccountRequest {
             account_id: get_account_id(),
-            amount: "10000".to_string(),
+            amount: "10000".parse().unwrap(),
             note: None,
         }, Some(&get_idempotency_key()))
         .await;
@@ -143,7 +143,7 @@ pub async fn deposit_account(rest_client: &BrandApiClient<ExampleBrandApiConfig>
     let resp = rest_client
         .deposit_account(&AccountOperationRequest {
             account_id: get_account_id(),
-            amount: "10000".to_string(),
+            amount: "10000".parse().unwrap(),
             note: None,
         }, Some(&get_idempotency_key()))
         .await;
@@ -155,7 +155,7 @@ pub async fn withdraw_account(rest_client: &BrandApiClient<ExampleBrandApiConfig
     let resp = rest_client
         .withdraw_account(&AccountOperationRequest {
             account_id: get_account_id(),
-            amount: "1000".to_string(),
+            amount: "1000".parse().unwrap(),
             note: None,
         }, Some(&get_idempotency_key()))
         .await;
@@ -165,10 +165,7 @@ pub async fn withdraw_account(rest_client: &BrandApiClient<ExampleBrandApiConfig
 
 pub async fn get_monthly_active_accounts(rest_client: &BrandApiClient<ExampleBrandApiConfig>) {
     let resp = rest_client
-        .get_monthly_active_accounts(&MonthlyActiveAccountsRequest {
-            for_month: "2024-12".to_string(),
-            return_type: "json".to_string(),
-        })
+        .get_monthly_active_accounts(&MonthlyActiveAccountsRequest::new(2024, 12))
         .await;
 
     println!("{:?}", resp)
@@ -176,12 +173,10 @@ pub async fn get_monthly_active_accounts(rest_client: &BrandApiClient<ExampleBra
 
 pub async fn get_closed_trades_report(rest_client: &BrandApiClient<ExampleBrandApiConfig>) {
     let resp = rest_client
-        .get_closed_trades_report(&GetClosedTradesReportRequest {
-            account_ids: None,
-            account_type: get_account_type(),
-            start_date_time: Utc::now().sub(TimeDelta::days(30)).to_rfc3339(),
-            end_date_time: Utc::now().to_rfc3339(),
-        })
+        .get_closed_trades_report(&GetClosedTradesReportRequest::new(
+            get_account_type(),
+            ReportDateRange::last_days(30),
+        ))
         .await;
 
     println!("{:?}", resp)
@@ -270,7 +265,7 @@ pub async fn get_accounts_report(rest_client: &BrandApiClient<ExampleBrandApiCon
     let resp = rest_client
         .get_accounts_report(&GetAccountsReportRequest {
             account_type: get_account_type(),
-            account_ids: Some(vec!["L#705519".to_string()]),
+            account_ids: Some(vec![AccountId::new("L#705519")]),
             account_status: Some(AccountStatus::Active),
         })
         .await;
@@ -312,13 +307,10 @@ pub async fn get_assets(rest_client: &BrandApiClient<ExampleBrandApiConfig>) {
 }
 
 pub async fn get_trades_report(rest_client: &BrandApiClient<ExampleBrandApiConfig>) {
-    let now = Utc::now();
-    let request = GetTradesReportRequest {
-        account_type: AccountType::Live,
-        account_ids: None,
-        start_date_time: Some(date_to_string(now.sub(TimeDelta::days(10)))),
-        end_date_time: Some(date_to_string(now)),
-    };
+    let request = GetTradesReportRequest::builder(AccountType::Live)
+        .last_days(10)
+        .build()
+        .unwrap();
     println!("==========");
     println!("{:?} sending {:?}", Utc::now(), request);
 
@@ -327,12 +319,6 @@ pub async fn get_trades_report(rest_client: &BrandApiClient<ExampleBrandApiConfi
     println!("{:?} got response {:?}", Utc::now(), resp,);
     println!("==========");
 }
-pub fn date_to_string(date: DateTime<Utc>) -> String {
-    format!("{}", date.format(FORMAT))
-}
-
-const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ"; // yyyy-MM-ddTHH:mm:ss.SSSZ e.g., 2021-12-31T23:59:59.999Z
-
 pub async fn load_test(rest_client: &BrandApiClient<ExampleBrandApiConfig>) {
     let max_parallel_requests: usize = 1;
     let num_requests: usize = 10000;
@@ -407,7 +393,7 @@ pub async fn get_orders(rest_client: &BrandApiClient<ExampleBrandApiConfig>) {
     let resp = rest_client
         .get_orders(&GetOrdersRequest {
             account_type: get_account_type(),
-            account_id: Some("L#708261".to_string()),
+            account_id: Some(AccountId::new("L#708261")),
             offset: None,
             limit: Some(1000),
         })
@@ -420,7 +406,7 @@ pub async fn cancel_order(rest_client: &BrandApiClient<ExampleBrandApiConfig>) {
     let resp = rest_client
         .cancel_order(&CancelOrderRequest {
             account_type: get_account_type(),
-            order_id: "72057594042846841".to_string(),
+            order_id: OrderId::new("72057594042846841"),
         })
         .await;
 
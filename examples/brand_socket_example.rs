@@ -5,6 +5,12 @@ use trade_locker_connector::brand_socket::api_client::{
 };
 use trade_locker_connector::brand_socket::callback::BrandSocketApiEventHandler;
 use trade_locker_connector::brand_socket::models::BrandSocketEvent;
+use trade_locker_connector::brand_socket::supervisor::{
+    BrandSocketConnectionState, BrandSocketReconnectPolicy,
+};
+use trade_locker_connector::brand_socket::watchdog::{
+    BrandSocketTradingHours, BrandSocketWatchdogConfig,
+};
 use trade_locker_connector::models::AccountType;
 
 #[tokio::main]
@@ -16,41 +22,20 @@ async fn main() {
         api_key,
     });
     let handler = Arc::new(ExampleBrandSocketApiEventHandler);
+    // reconnects with backoff whenever the connection is lost, does not sync in time
+    // or stays silent for two minutes while the markets are open
+    let brand_api = BrandSocketApiClient::new(handler, config, Arc::new(ConsoleLogger))
+        .with_reconnect_policy(BrandSocketReconnectPolicy::default())
+        .with_watchdog(BrandSocketWatchdogConfig {
+            force_reconnect: true,
+            trading_hours: Some(BrandSocketTradingHours::forex()),
+            ..Default::default()
+        });
+    brand_api.connect().await.unwrap();
 
     loop {
-        let brand_api =
-            BrandSocketApiClient::new(handler.clone(), config.clone(), Arc::new(ConsoleLogger));
-        let result = brand_api.connect().await;
-
-        if let Err(error) = result {
-            println!("Error connect: {:?}", error);
-            continue;
-        }
-
-        let result = brand_api
-            .wait_until_sync_ended(std::time::Duration::from_secs(15))
-            .await;
-
-        if let Err(error) = result {
-            println!("===========================");
-            println!("Error wait_until_sync_ended: {:?}", error);
-            println!("===========================");
-            _ = brand_api.disconnect().await;
-            continue;
-        }
-        
-        if !brand_api.is_connected().await {
-            panic!("brand_api is not connected");
-        }
-        
-        println!("===========================");
-        println!("SYNC ENDED");
-        println!("===========================");
-        break;
-    }
-
-    loop {
-        tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+        tokio::time::sleep(tokio::time::Duration::from_secs(60)).await;
+        println!("health: {:?}", brand_api.get_health());
     }
 }
 
@@ -87,6 +72,14 @@ impl BrandSocketApiEventHandler for ExampleBrandSocketApiEventHandler {
     }
 
     async fn on_disconnected(&self) {}
+
+    async fn on_connection_state_changed(&self, state: BrandSocketConnectionState) {
+        println!("on_connection_state_changed: {:?}", state);
+    }
+
+    async fn on_stream_stale(&self, idle_for: std::time::Duration) {
+        println!("on_stream_stale: no events for {:?}", idle_for);
+    }
 }
 
 pub struct ConsoleLogger;
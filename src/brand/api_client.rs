@@ -1,15 +1,29 @@
+use crate::brand::bulk::{BulkOperationOptions, BulkOperationReport};
 use crate::brand::endpoints::BrandApiEndpoint;
-use crate::brand::errors::Error;
+use crate::brand::errors::{BrandApiError, BrandApiErrorDetails, BrandApiErrorResponse, Error};
 use crate::brand::models::CreateUserRequest;
+use crate::brand::idempotency::{IdempotencyKey, IdempotentResponse};
+use crate::brand::pagination::{
+    BrandApiPagination, CursorPaginatedRequest, CursorPaginatedResponse, OffsetPaginatedStream,
+};
+use crate::brand::rate_limiter::{parse_retry_after, BrandApiRateLimiter};
+use crate::brand::report_range::{merge_report_items, split_date_range, MAX_REPORT_WINDOW};
+use crate::brand::retry::BrandApiRetryPolicy;
+use crate::brand::transport::{
+    BrandApiTransport, BrandApiTransportRequest, BrandApiTransportResponse, FlUrlBrandApiTransport,
+};
+use crate::brand::validation::{validate_date_range, ValidateRequest};
 use crate::brand::{
-    AccountModel, AccountOperationRequest, AccountOperationResponse, CancelOrderRequest, CheckEmailRequest, CheckEmailResponse, CloseAccountPositionsRequest, CloseAccountPositionsResponse, CreateAccountRequest, CreateUserResponse, CreditAccountRequest, CreditAccountResponse, GetAccountRequest, GetAccountsReportRequest, GetAccountsReportResponse, GetApiStatusResponse, GetAssetsRequest, GetAssetsResponse, GetClosedPositionsReportRequest, GetClosedPositionsReportResponse, GetClosedTradesReportRequest, GetClosedTradesReportResponse, GetGroupsRequest, GetGroupsResponse, GetInstrumentsRequest, GetInstrumentsResponse, GetOpenedPositionsRequest, GetOpenedPositionsResponse, GetOrdersRequest, GetOrdersResponse, GetTradesReportRequest, GetTradesReportResponse, MonthlyActiveAccountsRequest, MonthlyActiveAccountsResponse, SetAccountGroupRequest, SetUserPasswordRequest, UpdateAccountStatusRequest, UpdateAccountStatusResponse
+    AccountModel, AccountOperationRequest, AccountOperationResponse, CancelOrderRequest, CheckEmailRequest, CheckEmailResponse, CloseAccountPositionsRequest, CloseAccountPositionsResponse, CreateAccountRequest, CreateUserResponse, CreditAccountRequest, CreditAccountResponse, GetAccountRequest, GetAccountsReportRequest, GetAccountsReportResponse, GetApiStatusResponse, GetAssetsRequest, GetAssetsResponse, GetClosedPositionsReportRequest, GetClosedPositionsReportResponse, GetClosedTradesReportRequest, GetClosedTradesReportResponse, GetGroupsRequest, GetGroupsResponse, GetInstrumentsRequest, GetInstrumentsResponse, GetOpenedPositionsRequest, GetOpenedPositionsResponse, GetOrdersRequest, GetOrdersResponse, GetTradesReportRequest, OrderModel, ClosedTradeReportModel, GetClosedTradesReportV1Response, GetCursorReportRequest, GetTradesReportV1Response, PageLinks, TradeReportModel, GetTradesReportResponse, MonthlyActiveAccountsRequest, MonthlyActiveAccountsResponse, ReturnType, SetAccountGroupRequest, SetUserPasswordRequest, UpdateAccountStatusRequest, UpdateAccountStatusResponse
 };
-use error_chain::bail;
-use flurl::{FlUrl, FlUrlResponse};
+use crate::models::{AccountId, GroupId};
+use flurl::FlUrl;
+use futures_util::{future, stream, Stream, StreamExt, TryStreamExt};
 use http::{Method, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::{Serialize};
 use std::fmt::Debug;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[async_trait::async_trait]
@@ -21,15 +35,38 @@ pub trait BrandApiConfig {
 
 pub struct BrandApiClient<C: BrandApiConfig> {
     config: C,
+    retry_policy: BrandApiRetryPolicy,
+    rate_limiter: Option<BrandApiRateLimiter>,
+    transport: Arc<dyn BrandApiTransport + Send + Sync>,
 }
 
 impl<C: BrandApiConfig> BrandApiClient<C> {
     pub fn new(config: C) -> Self {
         Self {
             config,
+            retry_policy: BrandApiRetryPolicy::no_retries(),
+            rate_limiter: None,
+            transport: Arc::new(FlUrlBrandApiTransport),
         }
     }
 
+    pub fn with_retry_policy(mut self, retry_policy: BrandApiRetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Replaces the default FlUrl based http stack.
+    pub fn with_transport(mut self, transport: Arc<dyn BrandApiTransport + Send + Sync>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Throttles requests client side. 429 responses with Retry-After pause the limiter.
+    pub fn with_rate_limiter(mut self, rate_limiter: BrandApiRateLimiter) -> Self {
+        self.rate_limiter = Some(rate_limiter);
+        self
+    }
+
     pub async fn create_user(
         &self,
         request: &CreateUserRequest,
@@ -97,9 +134,12 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
         self.send_deserialized(endpoint, Some(request), None).await
     }
 
+    /// The API answers with an empty body.
     pub async fn set_account_group(&self, request: &SetAccountGroupRequest) -> Result<(), Error> {
         let endpoint = BrandApiEndpoint::SetAccountGroup;
-        self.send_deserialized(endpoint, Some(request), None).await
+        let _resp = self.send(endpoint, Some(request), None).await?;
+
+        Ok(())
     }
 
     pub async fn close_account_positions(
@@ -142,6 +182,98 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
             .await
     }
 
+    /// Same as create_user but always sends an Idempotency-Key and returns it with the response.
+    /// The key is derived from `business_id` if provided, otherwise generated.
+    pub async fn create_user_idempotent(
+        &self,
+        request: &CreateUserRequest,
+        business_id: Option<&str>,
+    ) -> Result<IdempotentResponse<CreateUserResponse>, Error> {
+        let idempotency_key = IdempotencyKey::resolve(BrandApiEndpoint::CreateUser, business_id);
+        let data = self
+            .create_user(request, Some(idempotency_key.as_str()))
+            .await?;
+
+        Ok(IdempotentResponse {
+            idempotency_key,
+            data,
+        })
+    }
+
+    /// Same as create_account but always sends an Idempotency-Key and returns it with the response.
+    /// The key is derived from `business_id` if provided, otherwise generated.
+    pub async fn create_account_idempotent(
+        &self,
+        request: &CreateAccountRequest,
+        business_id: Option<&str>,
+    ) -> Result<IdempotentResponse<AccountModel>, Error> {
+        let idempotency_key =
+            IdempotencyKey::resolve(BrandApiEndpoint::CreateAccount, business_id);
+        let data = self
+            .create_account(request, Some(idempotency_key.as_str()))
+            .await?;
+
+        Ok(IdempotentResponse {
+            idempotency_key,
+            data,
+        })
+    }
+
+    /// Same as credit_account but always sends an Idempotency-Key and returns it with the response.
+    /// The key is derived from `business_id` if provided, otherwise generated.
+    pub async fn credit_account_idempotent(
+        &self,
+        request: &CreditAccountRequest,
+        business_id: Option<&str>,
+    ) -> Result<IdempotentResponse<CreditAccountResponse>, Error> {
+        let idempotency_key =
+            IdempotencyKey::resolve(BrandApiEndpoint::CreditAccount, business_id);
+        let data = self
+            .credit_account(request, Some(idempotency_key.as_str()))
+            .await?;
+
+        Ok(IdempotentResponse {
+            idempotency_key,
+            data,
+        })
+    }
+
+    /// Same as deposit_account but always sends an Idempotency-Key and returns it with the response.
+    /// The key is derived from `business_id` if provided, otherwise generated.
+    pub async fn deposit_account_idempotent(
+        &self,
+        request: &AccountOperationRequest,
+        business_id: Option<&str>,
+    ) -> Result<IdempotentResponse<AccountOperationResponse>, Error> {
+        let idempotency_key = IdempotencyKey::resolve(BrandApiEndpoint::Deposit, business_id);
+        let data = self
+            .deposit_account(request, Some(idempotency_key.as_str()))
+            .await?;
+
+        Ok(IdempotentResponse {
+            idempotency_key,
+            data,
+        })
+    }
+
+    /// Same as withdraw_account but always sends an Idempotency-Key and returns it with the response.
+    /// The key is derived from `business_id` if provided, otherwise generated.
+    pub async fn withdraw_account_idempotent(
+        &self,
+        request: &AccountOperationRequest,
+        business_id: Option<&str>,
+    ) -> Result<IdempotentResponse<AccountOperationResponse>, Error> {
+        let idempotency_key = IdempotencyKey::resolve(BrandApiEndpoint::Withdraw, business_id);
+        let data = self
+            .withdraw_account(request, Some(idempotency_key.as_str()))
+            .await?;
+
+        Ok(IdempotentResponse {
+            idempotency_key,
+            data,
+        })
+    }
+
     pub async fn get_instruments(
         &self,
         request: &GetInstrumentsRequest,
@@ -220,12 +352,127 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
         self.send_deserialized(endpoint, Some(request), None).await
     }
 
+    /// Streams all orders matching the request page by page, starting from the request offset.
+    /// The request limit is ignored in favor of `pagination.page_size`, and up to
+    /// `pagination.prefetch` pages are fetched ahead of the one being consumed.
+    /// The offset advances by the number of returned orders, and the stream ends on the first
+    /// empty page rather than on a short one: the server may cap the page size below the
+    /// requested one, so a short page does not mean the orders are exhausted. This costs one
+    /// extra request at the end. The stream ends after the first error.
+    pub fn get_orders_stream<'a>(
+        &'a self,
+        request: &'a GetOrdersRequest,
+        pagination: BrandApiPagination,
+    ) -> impl Stream<Item = Result<OrderModel, Error>> + 'a {
+        let offset = request.offset.unwrap_or(0);
+
+        OffsetPaginatedStream::new(offset, pagination, move |offset, limit| {
+            let page_request = GetOrdersRequest {
+                account_type: request.account_type.clone(),
+                account_id: request.account_id.clone(),
+                offset: Some(offset),
+                limit: Some(limit),
+            };
+
+            Box::pin(async move { Ok(self.get_orders(&page_request).await?.data) })
+        })
+    }
+
+    /// Single page of the v1 closed trades report.
+    pub async fn get_closed_trades_report_v1(
+        &self,
+        request: &GetCursorReportRequest,
+    ) -> Result<GetClosedTradesReportV1Response, Error> {
+        let endpoint = BrandApiEndpoint::GetClosedTradesHistoryReportV1;
+        self.send_deserialized(endpoint, Some(request), None).await
+    }
+
+    /// All pages of the v1 closed trades report, starting from the request cursor.
+    pub fn get_closed_trades_report_v1_stream(
+        &self,
+        request: GetCursorReportRequest,
+    ) -> impl Stream<Item = Result<ClosedTradeReportModel, Error>> + '_ {
+        let endpoint = BrandApiEndpoint::GetClosedTradesHistoryReportV1;
+        self.send_cursor_paginated::<_, GetClosedTradesReportV1Response>(endpoint, request)
+    }
+
+    /// Single page of the v1 trades report.
+    pub async fn get_trades_report_v1(
+        &self,
+        request: &GetCursorReportRequest,
+    ) -> Result<GetTradesReportV1Response, Error> {
+        let endpoint = BrandApiEndpoint::GetTradesHistoryReportV1;
+        self.send_deserialized(endpoint, Some(request), None).await
+    }
+
+    /// All pages of the v1 trades report, starting from the request cursor.
+    pub fn get_trades_report_v1_stream(
+        &self,
+        request: GetCursorReportRequest,
+    ) -> impl Stream<Item = Result<TradeReportModel, Error>> + '_ {
+        let endpoint = BrandApiEndpoint::GetTradesHistoryReportV1;
+        self.send_cursor_paginated::<_, GetTradesReportV1Response>(endpoint, request)
+    }
+
+    /// Streams the items of an endpoint paginated with a cursor. Follows `links.next.params.cursor`
+    /// until a page is empty, has no next link or repeats the cursor.
+    /// The stream ends after the first error.
+    pub fn send_cursor_paginated<'a, R, P>(
+        &'a self,
+        endpoint: BrandApiEndpoint,
+        request: R,
+    ) -> impl Stream<Item = Result<P::Item, Error>> + 'a
+    where
+        R: CursorPaginatedRequest + Serialize + ValidateRequest + Debug + 'a,
+        P: CursorPaginatedResponse + DeserializeOwned + Debug + 'a,
+    {
+        stream::unfold(Some(request), move |request| async move {
+            let mut request = request?;
+            let page: P = match self.send_deserialized(endpoint, Some(&request), None).await {
+                Ok(page) => page,
+                Err(err) => return Some((Err(err), None)),
+            };
+            let (items, links) = page.into_page();
+            let next_cursor = links
+                .as_ref()
+                .and_then(PageLinks::get_next_cursor)
+                .filter(|cursor| !items.is_empty() && *cursor != request.get_cursor())
+                .map(str::to_string);
+            let next_request = next_cursor.map(|cursor| {
+                request.set_cursor(cursor);
+                request
+            });
+
+            Some((Ok(items), next_request))
+        })
+        .flat_map(|page| match page {
+            Ok(items) => stream::iter(items.into_iter().map(Ok)).left_stream(),
+            Err(err) => stream::once(future::ready(Err(err))).right_stream(),
+        })
+    }
+
     pub async fn get_monthly_active_accounts(
         &self,
         request: &MonthlyActiveAccountsRequest,
     ) -> Result<MonthlyActiveAccountsResponse, Error> {
         let endpoint = BrandApiEndpoint::MonthlyActiveAccounts;
-        self.send_deserialized(endpoint, Some(request), None).await
+
+        match request.return_type {
+            ReturnType::Json => self.send_deserialized(endpoint, Some(request), None).await,
+            ReturnType::Csv => {
+                let data = self.send_csv_deserialized(endpoint, Some(request), None).await?;
+                Ok(MonthlyActiveAccountsResponse { data })
+            }
+        }
+    }
+
+    /// Returns the response body as is, e.g. the binary CSV for ReturnType::Csv.
+    pub async fn get_monthly_active_accounts_raw(
+        &self,
+        request: &MonthlyActiveAccountsRequest,
+    ) -> Result<Vec<u8>, Error> {
+        let endpoint = BrandApiEndpoint::MonthlyActiveAccounts;
+        self.send_raw(endpoint, Some(request), None).await
     }
 
     pub async fn get_closed_positions_report(
@@ -235,7 +482,198 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
         let endpoint = BrandApiEndpoint::GetClosedPositionsHistoryReport;
         self.send_deserialized(endpoint, Some(request), None).await
     }
-    async fn send<R: Serialize + Debug>(
+
+    /// Requests the closed trades report for a range of any length by splitting it into
+    /// windows of at most 31 days. Up to `max_concurrency` windows are requested at once.
+    /// Trades are deduplicated by close trade id and sorted by close time.
+    pub async fn get_closed_trades_report_for_range(
+        &self,
+        request: &GetClosedTradesReportRequest,
+        max_concurrency: usize,
+    ) -> Result<GetClosedTradesReportResponse, Error> {
+        validate_date_range(request.start_date_time, request.end_date_time, None)?;
+        let windows = split_date_range(
+            request.start_date_time,
+            request.end_date_time,
+            MAX_REPORT_WINDOW,
+        );
+        let responses: Vec<GetClosedTradesReportResponse> =
+            stream::iter(windows.into_iter().map(|(start_date_time, end_date_time)| {
+                let request = GetClosedTradesReportRequest {
+                    start_date_time,
+                    end_date_time,
+                    ..request.clone()
+                };
+
+                async move { self.get_closed_trades_report(&request).await }
+            }))
+            .buffered(max_concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        let data = merge_report_items(
+            responses.into_iter().flat_map(|resp| resp.data).collect(),
+            |trade| trade.close_trade_id.clone(),
+            |trade| trade.close_milliseconds,
+        );
+
+        Ok(GetClosedTradesReportResponse { data })
+    }
+
+    /// Requests the closed positions report for a range of any length by splitting it into
+    /// windows of at most 31 days. Up to `max_concurrency` windows are requested at once.
+    /// Positions are deduplicated by position id and sorted by close time.
+    pub async fn get_closed_positions_report_for_range(
+        &self,
+        request: &GetClosedPositionsReportRequest,
+        max_concurrency: usize,
+    ) -> Result<GetClosedPositionsReportResponse, Error> {
+        validate_date_range(request.start_date_time, request.end_date_time, None)?;
+        let windows = split_date_range(
+            request.start_date_time,
+            request.end_date_time,
+            MAX_REPORT_WINDOW,
+        );
+        let responses: Vec<GetClosedPositionsReportResponse> =
+            stream::iter(windows.into_iter().map(|(start_date_time, end_date_time)| {
+                let request = GetClosedPositionsReportRequest {
+                    start_date_time,
+                    end_date_time,
+                    ..request.clone()
+                };
+
+                async move { self.get_closed_positions_report(&request).await }
+            }))
+            .buffered(max_concurrency.max(1))
+            .try_collect()
+            .await?;
+
+        let data = merge_report_items(
+            responses.into_iter().flat_map(|resp| resp.data).collect(),
+            |position| position.position_id.clone(),
+            |position| position.close_date_time,
+        );
+
+        Ok(GetClosedPositionsReportResponse { data })
+    }
+
+    /// Restricts the accounts as fast as the options allow. Failures are reported per account
+    /// and do not stop the rest.
+    pub async fn restrict_accounts(
+        &self,
+        account_ids: &[AccountId],
+        options: BulkOperationOptions,
+    ) -> BulkOperationReport<UpdateAccountStatusResponse> {
+        let endpoint = BrandApiEndpoint::RestrictAccount;
+
+        self.run_bulk(endpoint, account_ids, options, |account_id| async move {
+            self.restrict_account(&UpdateAccountStatusRequest::new(account_id))
+                .await
+        })
+        .await
+    }
+
+    /// Suspends the accounts as fast as the options allow. Failures are reported per account
+    /// and do not stop the rest.
+    pub async fn suspend_accounts(
+        &self,
+        account_ids: &[AccountId],
+        options: BulkOperationOptions,
+    ) -> BulkOperationReport<UpdateAccountStatusResponse> {
+        let endpoint = BrandApiEndpoint::SuspendAccount;
+
+        self.run_bulk(endpoint, account_ids, options, |account_id| async move {
+            self.suspend_account(&UpdateAccountStatusRequest::new(account_id))
+                .await
+        })
+        .await
+    }
+
+    /// Activates the accounts as fast as the options allow. Failures are reported per account
+    /// and do not stop the rest.
+    pub async fn activate_accounts(
+        &self,
+        account_ids: &[AccountId],
+        options: BulkOperationOptions,
+    ) -> BulkOperationReport<UpdateAccountStatusResponse> {
+        let endpoint = BrandApiEndpoint::ActivateAccount;
+
+        self.run_bulk(endpoint, account_ids, options, |account_id| async move {
+            self.activate_account(&UpdateAccountStatusRequest::new(account_id))
+                .await
+        })
+        .await
+    }
+
+    /// Moves the accounts into the group as fast as the options allow. Failures are reported
+    /// per account and do not stop the rest.
+    pub async fn set_accounts_group(
+        &self,
+        account_ids: &[AccountId],
+        group_id: &GroupId,
+        options: BulkOperationOptions,
+    ) -> BulkOperationReport<()> {
+        let endpoint = BrandApiEndpoint::SetAccountGroup;
+
+        self.run_bulk(endpoint, account_ids, options, |account_id| async move {
+            self.set_account_group(&SetAccountGroupRequest::new(account_id, group_id.clone()))
+                .await
+        })
+        .await
+    }
+
+    /// Closes the positions of the accounts as fast as the options allow. Failures are reported
+    /// per account and do not stop the rest.
+    pub async fn close_accounts_positions(
+        &self,
+        account_ids: &[AccountId],
+        options: BulkOperationOptions,
+    ) -> BulkOperationReport<CloseAccountPositionsResponse> {
+        let endpoint = BrandApiEndpoint::CloseAccountPositions;
+
+        self.run_bulk(endpoint, account_ids, options, |account_id| async move {
+            self.close_account_positions(&CloseAccountPositionsRequest::new(account_id))
+                .await
+        })
+        .await
+    }
+
+    async fn run_bulk<'a, T, F, Fut>(
+        &'a self,
+        endpoint: BrandApiEndpoint,
+        account_ids: &'a [AccountId],
+        options: BulkOperationOptions,
+        operation: F,
+    ) -> BulkOperationReport<T>
+    where
+        F: Fn(AccountId) -> Fut,
+        Fut: std::future::Future<Output = Result<T, Error>> + 'a,
+    {
+        // Own budget of this call, requests still go through the limiter of the client in send.
+        let rate_limiter = options
+            .rate_limit
+            .map(|rate_limit| BrandApiRateLimiter::new().with_default_limit(rate_limit));
+        let rate_limiter = &rate_limiter;
+
+        let results = stream::iter(account_ids.iter().map(|account_id| {
+            let future = operation(account_id.clone());
+
+            async move {
+                if let Some(rate_limiter) = rate_limiter {
+                    rate_limiter.acquire(endpoint).await;
+                }
+
+                (account_id.clone(), future.await)
+            }
+        }))
+        .buffered(options.max_concurrency.get())
+        .collect()
+        .await;
+
+        BulkOperationReport { results }
+    }
+
+    async fn send<R: Serialize + ValidateRequest + Debug>(
         &self,
         endpoint: BrandApiEndpoint,
         request: Option<&R>,
@@ -245,26 +683,50 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
             println!("execute send: {:?} {:?}", endpoint, request);
         }
 
-        let timeout = self.config.get_timeout().await;
-        let response = tokio::time::timeout(
-            timeout,
-            self.send_flurl(&endpoint, request, idempotency_key),
-        )
-        .await;
+        let request = self.build_request(endpoint, request, idempotency_key).await?;
+        let response = self.send_request(&request).await?;
 
-        let Ok(response) = response else {
-            let msg = format!(
-                "Failed {:?} {:?}: Timeout",
-                endpoint.get_http_method(),
-                endpoint
-            );
-            return Err(msg.into());
-        };
+        Ok(String::from_utf8_lossy(&response).to_string())
+    }
 
-        response
+    async fn send_raw<R: Serialize + ValidateRequest + Debug>(
+        &self,
+        endpoint: BrandApiEndpoint,
+        request: Option<&R>,
+        idempotency_key: Option<&str>,
+    ) -> Result<Vec<u8>, Error> {
+        if std::env::var("DEBUG").is_ok() {
+            println!("execute send_raw: {:?} {:?}", endpoint, request);
+        }
+
+        let request = self.build_request(endpoint, request, idempotency_key).await?;
+        self.send_request(&request).await
     }
 
-    async fn send_deserialized<R: Serialize + Debug, T: DeserializeOwned + Debug>(
+    async fn send_csv_deserialized<R: Serialize + ValidateRequest + Debug, T: DeserializeOwned>(
+        &self,
+        endpoint: BrandApiEndpoint,
+        request: Option<&R>,
+        idempotency_key: Option<&str>,
+    ) -> Result<Vec<T>, Error> {
+        if std::env::var("DEBUG").is_ok() {
+            println!("execute send_csv_deserialized: {:?} {:?}", endpoint, request);
+        }
+
+        let request = self.build_request(endpoint, request, idempotency_key).await?;
+        let response = self.send_request(&request).await?;
+
+        match parse_csv(&response) {
+            Ok(rows) => Ok(rows),
+            Err(err) => {
+                let body = String::from_utf8_lossy(&response).to_string();
+                let details = request.get_error_details(None, Some(body), None, err.to_string());
+                Err(BrandApiError::Deserialization(details).into())
+            }
+        }
+    }
+
+    async fn send_deserialized<R: Serialize + ValidateRequest + Debug, T: DeserializeOwned + Debug>(
         &self,
         endpoint: BrandApiEndpoint,
         request: Option<&R>,
@@ -274,25 +736,121 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
             println!("execute send_deserialized: {:?} {:?}", endpoint, request);
         }
 
+        let request = self.build_request(endpoint, request, idempotency_key).await?;
+        let response = self.send_request(&request).await?;
+        let result: Result<T, _> = serde_json::from_slice(&response);
+
+        match result {
+            Ok(body) => Ok(body),
+            Err(err) => {
+                let body = String::from_utf8_lossy(&response).to_string();
+                let details = request.get_error_details(None, Some(body), None, err.to_string());
+                Err(BrandApiError::Deserialization(details).into())
+            }
+        }
+    }
+
+    async fn build_request<R: Serialize + ValidateRequest>(
+        &self,
+        endpoint: BrandApiEndpoint,
+        request: Option<&R>,
+        idempotency_key: Option<&str>,
+    ) -> Result<BrandApiRequest, Error> {
+        if let Some(request) = request {
+            request.validate()?;
+        }
+
+        let base_url = self.config.get_api_url().await;
+        let url = self.build_url(&base_url, &endpoint, request);
+        let body = if let Some(request) = request {
+            Some(serde_json::to_string(request)?)
+        } else {
+            None
+        };
+
+        Ok(BrandApiRequest {
+            endpoint,
+            url,
+            body,
+            idempotency_key: idempotency_key.map(str::to_string),
+        })
+    }
+
+    async fn send_request(&self, request: &BrandApiRequest) -> Result<Vec<u8>, Error> {
+        let mut attempt = 1;
+
+        loop {
+            let result = self.send_request_once(request).await;
+
+            let Err(err) = result else {
+                return result;
+            };
+
+            let Some(api_err) = err.as_api_error() else {
+                return Err(err);
+            };
+
+            let retry_after = api_err.details().retry_after;
+
+            if let (Some(rate_limiter), Some(retry_after)) = (&self.rate_limiter, retry_after) {
+                rate_limiter.pause(retry_after).await;
+            }
+
+            let should_retry = self.retry_policy.should_retry(
+                request.endpoint,
+                request.idempotency_key.is_some(),
+                attempt,
+                api_err,
+            );
+
+            if !should_retry {
+                return Err(err);
+            }
+
+            let backoff = self.retry_policy.get_backoff(attempt);
+            let backoff = retry_after.map_or(backoff, |retry_after| retry_after.max(backoff));
+
+            if std::env::var("DEBUG").is_ok() {
+                println!("retry {:?} in {:?} after attempt {}: {}", request.endpoint, backoff, attempt, err);
+            }
+
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+
+    async fn send_request_once(&self, request: &BrandApiRequest) -> Result<Vec<u8>, Error> {
+        let _permit = match &self.rate_limiter {
+            Some(rate_limiter) => Some(rate_limiter.acquire(request.endpoint).await),
+            None => None,
+        };
         let timeout = self.config.get_timeout().await;
-        let response = tokio::time::timeout(
-            timeout,
-            self.send_flurl_deserialized(&endpoint, request, idempotency_key),
-        )
-        .await;
+        let response =
+            tokio::time::timeout(timeout, self.send_transport(request)).await;
 
         let Ok(response) = response else {
-            let msg = format!(
-                "Failed {:?} {:?}: Timeout",
-                endpoint.get_http_method(),
-                endpoint
-            );
-            return Err(msg.into());
+            let details =
+                request.get_error_details(None, None, None, format!("No response within {:?}", timeout));
+            return Err(BrandApiError::Timeout(details).into());
         };
 
         response
     }
 
+    fn build_url<R: Serialize>(
+        &self,
+        base_url: &str,
+        endpoint: &BrandApiEndpoint,
+        request: Option<&R>,
+    ) -> String {
+        if endpoint.get_http_method() == Method::GET {
+            let query_string = serde_qs::to_string(&request).expect("must be valid model");
+            self.build_full_url(base_url, endpoint, Some(query_string))
+        } else {
+            self.build_full_url(base_url, endpoint, None)
+        }
+    }
+
     fn build_full_url(
         &self,
         base_url: &str,
@@ -308,78 +866,29 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
         }
     }
 
-    async fn send_flurl_deserialized<R: Serialize + Debug, T: DeserializeOwned + Debug>(
-        &self,
-        endpoint: &BrandApiEndpoint,
-        request: Option<&R>,
-        idempotency_key: Option<&str>,
-    ) -> Result<T, Error> {
-        let response = self.send_flurl(endpoint, request, idempotency_key).await?;
-        let result: Result<T, _> = serde_json::from_str(&response);
-
-        let Ok(body) = result else {
-            let msg = format!(
-                "Failed to deserialize. Err: {:?} Url: {:?} {:?}. Request: {:?}. Body: {}",
-                result.unwrap_err(),
-                endpoint.get_http_method(),
-                String::from(endpoint),
-                request,
-                response
-            );
-            return Err(msg.into());
+    async fn send_transport(&self, request: &BrandApiRequest) -> Result<Vec<u8>, Error> {
+        let transport_request = BrandApiTransportRequest {
+            method: request.endpoint.get_http_method(),
+            url: request.url.clone(),
+            headers: self.get_headers(request.idempotency_key.as_deref()).await,
+            body: request.body.as_ref().map(|body| body.as_bytes().to_vec()),
+            timeout: self.config.get_timeout().await,
         };
 
-        Ok(body)
-    }
-
-    async fn send_flurl<R: Serialize + Debug>(
-        &self,
-        endpoint: &BrandApiEndpoint,
-        request: Option<&R>,
-        idempotency_key: Option<&str>,
-    ) -> Result<String, Error> {
-        let mut request_json = None;
-
-        if let Some(request) = request {
-            let body = serde_json::to_string(request)?;
-            request_json = Some(body.clone());
-        }
-
-        let request_bytes: Option<Vec<u8>> = if let Some(request) = request {
-            Some(serde_json::to_string(request)?.into_bytes())
-        } else {
-            None
-        };
-        let (flurl, url) = self.build_flurl(endpoint, request, idempotency_key).await?;
-        let http_method = endpoint.get_http_method();
-
-        let result = if http_method == Method::GET {
-            flurl.get().await
-        } else if http_method == Method::POST {
-            flurl.post(request_bytes).await
-        } else if http_method == Method::PUT {
-            flurl.put(request_bytes).await
-        } else if http_method == Method::PATCH {
-            flurl.patch(request_bytes).await
-        } else if http_method == Method::DELETE {
-            flurl.delete().await
-        } else {
-            panic!("not implemented");
+        let response = match self.transport.send(transport_request).await {
+            Ok(response) => response,
+            Err(err) => {
+                let details = request.get_error_details(None, None, None, err);
+                return Err(BrandApiError::Transport(details).into());
+            }
         };
 
-        let Ok(resp) = result else {
-            return Err(format!(
-                "FlUrl failed to receive_body: Url: {}. Request: {:?}. {:?}",
-                url,
-                request_json,
-                result.unwrap_err()
-            )
-            .into());
-        };
-
-        handle_flurl_text(resp, &request_json, &url, endpoint.get_http_method()).await
+        handle_response(response, request)
     }
 
+    /// Builds a FlUrl with the headers of the client. The request bypasses the transport,
+    /// the rate limiter and the retry policy.
+    #[deprecated(note = "requests go through BrandApiTransport, see with_transport")]
     pub async fn build_flurl<R: Serialize>(
         &self,
         endpoint: &BrandApiEndpoint,
@@ -387,33 +896,33 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
         idempotency_key: Option<&str>,
     ) -> Result<(FlUrl, String), Error> {
         let base_url = self.config.get_api_url().await;
-        let http_method = endpoint.get_http_method();
-
-        let url = if http_method == Method::GET {
-            let query_string = serde_qs::to_string(&request).expect("must be valid model");
-            self.build_full_url(&base_url, endpoint, Some(query_string))
-        } else {
-            self.build_full_url(&base_url, endpoint, None)
-        };
-
+        let url = self.build_url(&base_url, endpoint, request);
         let flurl = self.add_headers(FlUrl::new(&url).set_timeout(self.config.get_timeout().await), idempotency_key).await;
 
         Ok((flurl, url))
     }
 
     async fn add_headers(&self, flurl: FlUrl, idempotency_key: Option<&str>) -> FlUrl {
+        self.get_headers(idempotency_key)
+            .await
+            .into_iter()
+            .fold(flurl, |flurl, (name, value)| flurl.with_header(&name, value))
+    }
+
+    async fn get_headers(&self, idempotency_key: Option<&str>) -> Vec<(String, String)> {
         let json_content_str = "application/json";
 
-        let mut flurl = flurl
-            .with_header("Content-Type", json_content_str)
-            .with_header("Accept", json_content_str)
-            .with_header("brand-api-key", self.config.get_api_key().await);
+        let mut headers = vec![
+            ("Content-Type".to_string(), json_content_str.to_string()),
+            ("Accept".to_string(), json_content_str.to_string()),
+            ("brand-api-key".to_string(), self.config.get_api_key().await),
+        ];
 
         if let Some(idempotency_key) = idempotency_key {
-            flurl = flurl.with_header("Idempotency-Key", idempotency_key);
+            headers.push(("Idempotency-Key".to_string(), idempotency_key.to_string()));
         }
 
-        flurl
+        headers
     }
 
     pub fn build_query_string(&self, params: Vec<(&str, &str)>) -> String {
@@ -430,53 +939,625 @@ impl<C: BrandApiConfig> BrandApiClient<C> {
     }
 }
 
-async fn handle_flurl_text(
-    response: FlUrlResponse,
-    request_json: &Option<String>,
-    request_url: &str,
-    request_method: Method,
-) -> Result<String, Error> {
-    let status_code = StatusCode::from_u16(response.get_status_code()).unwrap();
-    let result = response.receive_body().await;
-
-    let Ok(body_bytes) = result else {
-        return Err(format!("FlUrl failed to receive_body: {:?}", result.unwrap_err()).into());
-    };
+struct BrandApiRequest {
+    endpoint: BrandApiEndpoint,
+    url: String,
+    body: Option<String>,
+    idempotency_key: Option<String>,
+}
+
+impl BrandApiRequest {
+    fn get_error_details(
+        &self,
+        status_code: Option<u16>,
+        body: Option<String>,
+        retry_after: Option<Duration>,
+        message: String,
+    ) -> BrandApiErrorDetails {
+        let error_response = body.as_deref().and_then(BrandApiErrorResponse::parse);
+        let error_code = error_response
+            .as_ref()
+            .and_then(|response| response.code.clone());
+        let message = match error_response.as_ref().and_then(|r| r.message.as_ref()) {
+            Some(response_message) if message.is_empty() => response_message.to_string(),
+            _ => message,
+        };
+
+        BrandApiErrorDetails {
+            endpoint: self.endpoint,
+            method: self.endpoint.get_http_method(),
+            url: self.url.clone(),
+            request: self.body.clone(),
+            idempotency_key: self.idempotency_key.clone(),
+            status_code,
+            body,
+            error_code,
+            retry_after,
+            error_response,
+            message,
+        }
+    }
+}
+
+fn handle_response(
+    response: BrandApiTransportResponse,
+    request: &BrandApiRequest,
+) -> Result<Vec<u8>, Error> {
+    let status_code = response.status_code;
+    let retry_after = response
+        .get_header("retry-after")
+        .and_then(parse_retry_after);
+
+    if StatusCode::from_u16(status_code).is_ok_and(|code| code.is_success()) {
+        return Ok(response.body);
+    }
+
+    let body_str = String::from_utf8_lossy(&response.body).to_string();
+    let details = request.get_error_details(Some(status_code), Some(body_str), retry_after, String::new());
+
+    Err(BrandApiError::from_status(status_code, details).into())
+}
+
+/// Reads CSV rows with a header line. Columns are matched to the model by their serde names.
+fn parse_csv<T: DeserializeOwned>(body: &[u8]) -> Result<Vec<T>, csv::Error> {
+    csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(body)
+        .deserialize()
+        .collect()
+}
 
-    let body_str = String::from_utf8(body_bytes).unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand::errors::{BrandApiValidationError, WebservicesErrorCode};
+    use crate::brand::mock_server::MockBrandApi;
+    use crate::brand::test_support::{get_client, TestConfig};
+    use crate::brand::{
+        get_default_cursor, ClosedPositionModel, ClosedPositionSide, MonthlyActiveAccountModel,
+    };
+    use crate::models::AccountType;
+    use chrono::{DateTime, TimeDelta, Utc};
+    use serde_json::{json, Value};
+    use std::collections::VecDeque;
+    use std::pin::pin;
+    use std::sync::Mutex;
+
+    /// Replies with queued responses and records received requests.
+    #[derive(Default)]
+    struct FakeTransport {
+        responses: Mutex<VecDeque<Result<BrandApiTransportResponse, String>>>,
+        requests: Mutex<Vec<BrandApiTransportRequest>>,
+    }
 
-    match status_code {
-        StatusCode::OK | StatusCode::CREATED | StatusCode::NO_CONTENT => Ok(body_str),
-        StatusCode::INTERNAL_SERVER_ERROR => {
-            bail!(format!(
-                "Internal Server Error. Url: {request_method:?} {request_url}. Request: {:?}. Response: {}", request_json, body_str,
-            ));
+    impl FakeTransport {
+        fn reply(self, status_code: u16, body: &str) -> Self {
+            self.responses
+                .lock()
+                .unwrap()
+                .push_back(Ok(BrandApiTransportResponse {
+                    status_code,
+                    headers: vec![],
+                    body: body.as_bytes().to_vec(),
+                }));
+            self
         }
-        StatusCode::SERVICE_UNAVAILABLE => {
-            bail!(format!(
-                "Service Unavailable. Url: {request_method:?} {request_url}. Request: {:?}. Response: {}", request_json, body_str
-            ));
+
+        fn get_requests(&self) -> Vec<BrandApiTransportRequest> {
+            self.requests.lock().unwrap().clone()
         }
-        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN => {
-            bail!(format!(
-                "Unauthorized or forbidden. Url: {request_method:?} {request_url}. Request: {:?}. Response: {}", request_json, body_str
-            ));
+    }
+
+    #[async_trait::async_trait]
+    impl BrandApiTransport for FakeTransport {
+        async fn send(
+            &self,
+            request: BrandApiTransportRequest,
+        ) -> Result<BrandApiTransportResponse, String> {
+            self.requests.lock().unwrap().push(request);
+            self.responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .unwrap_or(Err("no response".to_string()))
         }
-        StatusCode::BAD_REQUEST => {
-            let error = body_str;
-            bail!(format!(
-                "Received bad request status. Url: {request_method:?} {request_url}. Request: {request_json:?}. Response: {error:?}"
-            ));
+    }
+
+    fn get_retry_policy() -> BrandApiRetryPolicy {
+        BrandApiRetryPolicy {
+            initial_backoff: Duration::from_millis(1),
+            ..Default::default()
         }
-        code => {
-            let error = body_str;
-            bail!(format!("Received response code: {code:?}. Url: {request_method:?} {request_url}. Request: {request_json:?} Response: {error:?}"));
+    }
+
+    fn get_operation_request() -> AccountOperationRequest {
+        AccountOperationRequest {
+            account_id: "L#1".into(),
+            amount: "100".parse().unwrap(),
+            note: None,
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
     #[test]
     fn works() {}
+
+    #[tokio::test]
+    async fn sends_request_with_headers() {
+        let transport = Arc::new(FakeTransport::default().reply(200, r#"{"operationId":"1"}"#));
+        let client = BrandApiClient::new(TestConfig).with_transport(transport.clone());
+
+        let resp = client
+            .deposit_account(&get_operation_request(), Some("key-1"))
+            .await
+            .unwrap();
+
+        assert_eq!(resp.operation_id, "1");
+        let request = &transport.get_requests()[0];
+        assert_eq!(request.method, Method::POST);
+        assert_eq!(
+            request.url,
+            "http://mock/brand-api/v1/account-operations/deposit"
+        );
+        assert_eq!(request.get_header("brand-api-key"), Some("key"));
+        assert_eq!(request.get_header("idempotency-key"), Some("key-1"));
+    }
+
+    #[tokio::test]
+    async fn returns_typed_error() {
+        let transport = Arc::new(
+            FakeTransport::default().reply(404, r#"{"code":"ACCOUNT_NOT_FOUND","message":"no"}"#),
+        );
+        let client = BrandApiClient::new(TestConfig).with_transport(transport);
+
+        let err = client
+            .get_account(&GetAccountRequest {
+                account_id: "L#1".into(),
+            })
+            .await
+            .unwrap_err();
+
+        let api_err = err.as_api_error().unwrap();
+        assert!(matches!(api_err, BrandApiError::NotFound(_)));
+        assert_eq!(
+            api_err.error_code(),
+            Some(&WebservicesErrorCode::AccountNotFound)
+        );
+        assert_eq!(api_err.details().message, "no");
+    }
+
+    #[tokio::test]
+    async fn returns_deserialization_error() {
+        let transport = Arc::new(FakeTransport::default().reply(200, "{}"));
+        let client = BrandApiClient::new(TestConfig).with_transport(transport);
+
+        let err = client
+            .get_groups(&GetGroupsRequest {
+                account_type: AccountType::Live,
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.as_api_error(),
+            Some(BrandApiError::Deserialization(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn returns_validation_error_without_sending() {
+        let transport = Arc::new(FakeTransport::default());
+        let client = BrandApiClient::new(TestConfig).with_transport(transport.clone());
+        let request = AccountOperationRequest {
+            amount: "0".parse().unwrap(),
+            ..get_operation_request()
+        };
+
+        let err = client.withdraw_account(&request, None).await.unwrap_err();
+
+        assert!(matches!(
+            err.as_validation_error(),
+            Some(BrandApiValidationError::NonPositiveAmount(_))
+        ));
+        assert!(transport.get_requests().is_empty());
+    }
+
+    #[tokio::test]
+    async fn retries_idempotent_endpoint() {
+        let transport = Arc::new(
+            FakeTransport::default()
+                .reply(503, "")
+                .reply(200, r#"{"data":[]}"#),
+        );
+        let client = BrandApiClient::new(TestConfig)
+            .with_transport(transport.clone())
+            .with_retry_policy(get_retry_policy());
+
+        client
+            .get_groups(&GetGroupsRequest {
+                account_type: AccountType::Live,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(transport.get_requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn does_not_retry_money_operation_without_key() {
+        let transport = Arc::new(
+            FakeTransport::default()
+                .reply(503, "")
+                .reply(200, r#"{"operationId":"1"}"#),
+        );
+        let client = BrandApiClient::new(TestConfig)
+            .with_transport(transport.clone())
+            .with_retry_policy(get_retry_policy());
+
+        let result = client.deposit_account(&get_operation_request(), None).await;
+
+        assert!(result.is_err());
+        assert_eq!(transport.get_requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn reuses_idempotency_key_across_retries() {
+        let transport = Arc::new(
+            FakeTransport::default()
+                .reply(500, "")
+                .reply(200, r#"{"operationId":"1"}"#),
+        );
+        let client = BrandApiClient::new(TestConfig)
+            .with_transport(transport.clone())
+            .with_retry_policy(get_retry_policy());
+
+        let resp = client
+            .deposit_account_idempotent(&get_operation_request(), Some("payout-1"))
+            .await
+            .unwrap();
+
+        let requests = transport.get_requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(
+            requests[0].get_header("Idempotency-Key"),
+            Some(resp.idempotency_key.as_str())
+        );
+        assert_eq!(
+            requests[1].get_header("Idempotency-Key"),
+            Some(resp.idempotency_key.as_str())
+        );
+    }
+
+    fn get_order(id: usize) -> Value {
+        json!({
+            "accountId": "L#1",
+            "amount": "1",
+            "lotSize": "100000",
+            "createdDateTime": "2024-01-01T00:00:00.000Z",
+            "filledAmount": "0",
+            "orderId": id.to_string(),
+            "price": "1.1",
+            "side": "buy",
+            "slPriceType": "absolute",
+            "status": "STATUS_PENDING_NEW",
+            "tif": "GTC",
+            "tpPriceType": "absolute",
+            "instrument": "EURUSD",
+            "type": "MARKET",
+        })
+    }
+
+    fn get_orders_request(offset: Option<i32>) -> GetOrdersRequest {
+        GetOrdersRequest {
+            account_type: AccountType::Live,
+            account_id: None,
+            offset,
+            limit: None,
+        }
+    }
+
+    fn get_mock_with_orders(count: usize) -> Arc<MockBrandApi> {
+        let api = Arc::new(MockBrandApi::new("key"));
+
+        for id in 0..count {
+            api.add_order(&serde_json::from_value(get_order(id)).unwrap());
+        }
+
+        api
+    }
+
+    #[tokio::test]
+    async fn streams_orders_by_pages() {
+        let api = get_mock_with_orders(5);
+        let client = get_client(&api);
+        let request = get_orders_request(Some(1));
+
+        let orders: Vec<OrderModel> = client
+            .get_orders_stream(&request, BrandApiPagination::new(2))
+            .try_collect()
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = orders.iter().map(|order| order.order_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "2", "3", "4"]);
+        // the last page is short, the empty one after it ends the stream
+        assert_eq!(api.get_requests_count(), 3);
+    }
+
+    #[tokio::test]
+    async fn streams_orders_from_pages_capped_by_server() {
+        let api = get_mock_with_orders(5);
+        api.set_max_orders_page_size(1);
+        let client = get_client(&api);
+        let request = get_orders_request(None);
+
+        let orders: Vec<OrderModel> = client
+            .get_orders_stream(&request, BrandApiPagination::new(2))
+            .try_collect()
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = orders.iter().map(|order| order.order_id.as_str()).collect();
+        assert_eq!(ids, vec!["0", "1", "2", "3", "4"]);
+    }
+
+    #[tokio::test]
+    async fn ends_orders_stream_after_error() {
+        let page = json!({ "data": [get_order(0), get_order(1)] }).to_string();
+        let transport = Arc::new(
+            FakeTransport::default()
+                .reply(200, &page)
+                .reply(500, "")
+                .reply(200, &page),
+        );
+        let client = BrandApiClient::new(TestConfig).with_transport(transport.clone());
+        let request = get_orders_request(None);
+
+        let results: Vec<Result<OrderModel, Error>> = client
+            .get_orders_stream(&request, BrandApiPagination::new(2).with_prefetch(2))
+            .collect()
+            .await;
+
+        assert_eq!(results.len(), 3);
+        assert!(results[..2].iter().all(Result::is_ok));
+        assert!(results[2].is_err());
+        let offsets: Vec<Value> = transport
+            .get_requests()
+            .iter()
+            .map(|request| serde_json::from_slice(request.body.as_deref().unwrap()).unwrap())
+            .map(|body: Value| body["offset"].clone())
+            .collect();
+        assert_eq!(offsets, vec![json!(0), json!(2)]);
+    }
+
+    #[tokio::test]
+    async fn prefetches_orders_pages() {
+        let api = get_mock_with_orders(5);
+        let client = get_client(&api);
+        let request = get_orders_request(None);
+
+        let mut orders = pin!(client.get_orders_stream(&request, BrandApiPagination::new(2)));
+        orders.next().await.unwrap().unwrap();
+        assert_eq!(api.get_requests_count(), 1);
+        orders.next().await.unwrap().unwrap();
+        assert_eq!(api.get_requests_count(), 1);
+        orders.next().await.unwrap().unwrap();
+        assert_eq!(api.get_requests_count(), 2);
+
+        let pagination = BrandApiPagination::new(2).with_prefetch(1);
+        let mut orders = pin!(client.get_orders_stream(&request, pagination));
+        orders.next().await.unwrap().unwrap();
+        // the second page is requested while the first one is consumed
+        assert_eq!(api.get_requests_count(), 4);
+        let rest: Vec<OrderModel> = orders.try_collect().await.unwrap();
+        assert_eq!(rest.len(), 4);
+        assert_eq!(api.get_requests_count(), 6);
+    }
+
+    fn get_closed_position(
+        position_id: &str,
+        close_date_time: DateTime<Utc>,
+    ) -> ClosedPositionModel {
+        ClosedPositionModel {
+            instrument: "EURUSD".to_string(),
+            lot_size: "100000".to_string(),
+            account_id: "L#1".into(),
+            close_trade_id: format!("T{}", position_id).into(),
+            position_id: position_id.into(),
+            close_order_id: "1".into(),
+            open_order_id: "2".into(),
+            duration_sec: "60".to_string(),
+            open_date_time: close_date_time - TimeDelta::minutes(1),
+            close_date_time,
+            profit: "10".parse().unwrap(),
+            net_profit: "9".parse().unwrap(),
+            commission: "1".parse().unwrap(),
+            swap: "0".parse().unwrap(),
+            amount: "1".to_string(),
+            open_price: "1.1".to_string(),
+            close_price: "1.2".to_string(),
+            sl_price: None,
+            tp_price: None,
+            side: ClosedPositionSide::Buy,
+            currency: "USD".to_string(),
+            open_trade_cross_price: "1".to_string(),
+            close_trade_cross_price: "1".to_string(),
+            user_group_id: "1".into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn splits_long_report_range() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let start = "2024-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        // the last one closes exactly on a window boundary and is returned twice
+        for (id, days) in [("3", 80), ("1", 5), ("2", 40), ("4", 31)] {
+            api.add_closed_position(&get_closed_position(id, start + TimeDelta::days(days)));
+        }
+        let request = GetClosedPositionsReportRequest {
+            account_ids: None,
+            account_type: AccountType::Live,
+            start_date_time: start,
+            end_date_time: start + TimeDelta::days(90),
+        };
+
+        let err = client
+            .get_closed_positions_report(&request)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.as_validation_error(),
+            Some(BrandApiValidationError::DateRangeTooLong { .. })
+        ));
+
+        let resp = client
+            .get_closed_positions_report_for_range(&request, 2)
+            .await
+            .unwrap();
+        let ids: Vec<&str> = resp.data.iter().map(|p| p.position_id.as_str()).collect();
+        assert_eq!(ids, vec!["1", "4", "2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn splits_long_closed_trades_report_range() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let start = "2024-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        // the last one closes exactly on a window boundary and is returned twice
+        for (id, days) in [("2", 45), ("1", 31)] {
+            let close_milliseconds = (start + TimeDelta::days(days)).timestamp_millis();
+            let trade = json!({
+                "instrument": "EURUSD",
+                "openMilliseconds": (close_milliseconds - 60_000).to_string(),
+                "orderType": "MARKET",
+                "side": "BUY",
+                "closeAmount": "1",
+                "averageOpenPrice": "1.1",
+                "closePrice": "1.2",
+                "closeMilliseconds": close_milliseconds.to_string(),
+                "openAmount": "1",
+                "closeTradeId": format!("T{}", id),
+                "openTradeId": "T0",
+                "closeOrderId": "1",
+                "positionId": id,
+                "openOrderId": "2",
+                "commission": "1",
+                "swap": "0",
+                "profit": "10",
+                "netProfit": "9",
+                "lotSize": "100000",
+                "accountId": "L#1",
+                "userGroupId": "1",
+            });
+            api.add_closed_trade(&serde_json::from_value(trade).unwrap());
+        }
+        let request = GetClosedTradesReportRequest {
+            account_ids: None,
+            account_type: AccountType::Live,
+            start_date_time: start,
+            end_date_time: start + TimeDelta::days(60),
+        };
+
+        let resp = client
+            .get_closed_trades_report_for_range(&request, 2)
+            .await
+            .unwrap();
+
+        let ids: Vec<&str> = resp.data.iter().map(|t| t.close_trade_id.as_str()).collect();
+        assert_eq!(ids, vec!["T1", "T2"]);
+    }
+
+    #[tokio::test]
+    async fn streams_cursor_pages() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        for id in 0..5 {
+            let trade: TradeReportModel = serde_json::from_value(json!({
+                "tradeId": id.to_string(),
+                "orderId": "1",
+                "accountId": "L#1",
+                "side": "BUY",
+                "orderType": "MARKET",
+                "positionStatus": "OPEN",
+                "tradeTime": 1704067200000i64 + id,
+                "tradeDateTime": 1704067200000i64 + id,
+                "price": "1.1",
+                "lots": "1",
+                "instrument": "EURUSD",
+                "positionId": "1",
+                "pnl": "0",
+                "executionFee": "0",
+                "netPnl": "0",
+            }))
+            .unwrap();
+            api.add_trade(&trade);
+        }
+        let request = GetCursorReportRequest {
+            account_type: AccountType::Live,
+            account_id: Some("L#1".into()),
+            start_date_time: None,
+            end_date_time: None,
+            cursor: get_default_cursor(),
+            limit: Some(2),
+        };
+
+        let page = client.get_trades_report_v1(&request).await.unwrap();
+        assert_eq!(page.data.len(), 2);
+        assert_eq!(page.links.unwrap().get_next_cursor(), Some("2"));
+
+        let trades: Vec<TradeReportModel> = client
+            .get_trades_report_v1_stream(request)
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<&str> = trades.iter().map(|trade| trade.trade_id.as_str()).collect();
+        assert_eq!(ids, vec!["0", "1", "2", "3", "4"]);
+    }
+
+    #[tokio::test]
+    async fn returns_monthly_active_accounts_as_csv() {
+        let account = MonthlyActiveAccountModel {
+            account_id: "L#1".into(),
+            group: "default".to_string(),
+            sessions: 3,
+            events: 10,
+            accounts: 1,
+            open_positions: 2,
+            orders: 5,
+        };
+        let csv =
+            "accountId,group,sessions,events,accounts,openPositions,orders\nL#1,default,3,10,1,2,5\n";
+        let transport = Arc::new(
+            FakeTransport::default()
+                .reply(200, csv)
+                .reply(200, csv)
+                .reply(200, &json!({ "data": [account] }).to_string()),
+        );
+        let client = BrandApiClient::new(TestConfig).with_transport(transport.clone());
+        let mut request = MonthlyActiveAccountsRequest {
+            for_month: "2024-01".to_string(),
+            return_type: ReturnType::Csv,
+        };
+
+        let raw = client.get_monthly_active_accounts_raw(&request).await.unwrap();
+        assert_eq!(String::from_utf8(raw).unwrap(), csv);
+
+        let csv = client.get_monthly_active_accounts(&request).await.unwrap();
+        request.return_type = ReturnType::Json;
+        let json = client.get_monthly_active_accounts(&request).await.unwrap();
+        assert_eq!(csv.data.len(), 1);
+        assert_eq!(csv.data[0].open_positions, json.data[0].open_positions);
+        assert_eq!(csv.data[0].account_id, json.data[0].account_id);
+
+        let return_types: Vec<ReturnType> = transport
+            .get_requests()
+            .iter()
+            .map(|request| serde_json::from_slice(request.body.as_deref().unwrap()).unwrap())
+            .map(|body: MonthlyActiveAccountsRequest| body.return_type)
+            .collect();
+        assert_eq!(
+            return_types,
+            vec![ReturnType::Csv, ReturnType::Csv, ReturnType::Json]
+        );
+    }
 }
@@ -0,0 +1,731 @@
+//! Constructors and builders of the Brand API request models.
+//! Required fields are arguments of `new` and `builder`, optional ones default to None.
+//! `build` validates the request the same way BrandApiClient does before sending it.
+
+use crate::brand::errors::BrandApiValidationError;
+use crate::brand::report_range::ReportDateRange;
+use crate::brand::validation::ValidateRequest;
+use crate::brand::{
+    get_default_cursor, AccountOperationRequest, AccountStatus, CancelOrderRequest,
+    CheckEmailRequest, CloseAccountPositionsRequest, CreateAccountRequest, CreateUserRequest,
+    CreditAccountRequest, GetAccountRequest, GetAccountsReportRequest, GetAssetsRequest,
+    GetClosedPositionsReportRequest, GetClosedTradesReportRequest, GetCursorReportRequest,
+    GetGroupsRequest, GetInstrumentsRequest, GetOpenedPositionsRequest, GetOrdersRequest,
+    GetTradesReportRequest, MonthlyActiveAccountsRequest, ReturnType, SetAccountGroupRequest,
+    SetUserPasswordRequest, UpdateAccountStatusRequest,
+};
+use crate::models::{AccountId, AccountType, Amount, GroupId, OrderId, UserId};
+
+fn push_account_id(account_ids: &mut Option<Vec<AccountId>>, account_id: impl Into<AccountId>) {
+    account_ids
+        .get_or_insert_with(Vec::new)
+        .push(account_id.into());
+}
+
+fn collect_account_ids(
+    account_ids: impl IntoIterator<Item = impl Into<AccountId>>,
+) -> Option<Vec<AccountId>> {
+    Some(account_ids.into_iter().map(Into::into).collect())
+}
+
+fn build<R: ValidateRequest>(request: R) -> Result<R, BrandApiValidationError> {
+    request.validate()?;
+
+    Ok(request)
+}
+
+impl CreateUserRequest {
+    pub fn new(email: impl Into<String>, password: impl Into<String>) -> Self {
+        Self {
+            email: email.into(),
+            password: password.into(),
+            first_name: None,
+            last_name: None,
+        }
+    }
+
+    pub fn builder(
+        email: impl Into<String>,
+        password: impl Into<String>,
+    ) -> CreateUserRequestBuilder {
+        CreateUserRequestBuilder {
+            request: Self::new(email, password),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateUserRequestBuilder {
+    request: CreateUserRequest,
+}
+
+impl CreateUserRequestBuilder {
+    pub fn with_first_name(mut self, first_name: impl Into<String>) -> Self {
+        self.request.first_name = Some(first_name.into());
+        self
+    }
+
+    pub fn with_last_name(mut self, last_name: impl Into<String>) -> Self {
+        self.request.last_name = Some(last_name.into());
+        self
+    }
+
+    pub fn build(self) -> Result<CreateUserRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl CheckEmailRequest {
+    pub fn new(email: impl Into<String>) -> Self {
+        Self {
+            email: email.into(),
+        }
+    }
+}
+
+impl SetUserPasswordRequest {
+    pub fn new(user_id: impl Into<UserId>, password: impl Into<String>) -> Self {
+        Self {
+            user_id: user_id.into(),
+            password: password.into(),
+        }
+    }
+}
+
+impl GetAccountRequest {
+    pub fn new(account_id: impl Into<AccountId>) -> Self {
+        Self {
+            account_id: account_id.into(),
+        }
+    }
+}
+
+impl CreateAccountRequest {
+    /// `currency` is the 3-letter ISO 4217 code of the account currency.
+    pub fn new(
+        user_id: impl Into<UserId>,
+        account_name: impl Into<String>,
+        account_type: AccountType,
+        currency: impl Into<String>,
+    ) -> Self {
+        Self {
+            user_id: user_id.into(),
+            account_name: account_name.into(),
+            account_type,
+            currency: currency.into(),
+            group_id: None,
+        }
+    }
+
+    pub fn builder(
+        user_id: impl Into<UserId>,
+        account_name: impl Into<String>,
+        account_type: AccountType,
+        currency: impl Into<String>,
+    ) -> CreateAccountRequestBuilder {
+        CreateAccountRequestBuilder {
+            request: Self::new(user_id, account_name, account_type, currency),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreateAccountRequestBuilder {
+    request: CreateAccountRequest,
+}
+
+impl CreateAccountRequestBuilder {
+    /// Places the account into the group instead of the brand's default one.
+    pub fn with_group_id(mut self, group_id: impl Into<GroupId>) -> Self {
+        self.request.group_id = Some(group_id.into());
+        self
+    }
+
+    pub fn build(self) -> Result<CreateAccountRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl UpdateAccountStatusRequest {
+    pub fn new(account_id: impl Into<AccountId>) -> Self {
+        Self {
+            account_id: account_id.into(),
+        }
+    }
+}
+
+impl SetAccountGroupRequest {
+    pub fn new(account_id: impl Into<AccountId>, group_id: impl Into<GroupId>) -> Self {
+        Self {
+            account_id: account_id.into(),
+            group_id: group_id.into(),
+        }
+    }
+}
+
+impl CloseAccountPositionsRequest {
+    pub fn new(account_id: impl Into<AccountId>) -> Self {
+        Self {
+            account_id: account_id.into(),
+        }
+    }
+}
+
+impl CreditAccountRequest {
+    pub fn new(account_id: impl Into<AccountId>, amount: Amount) -> Self {
+        Self {
+            account_id: account_id.into(),
+            amount,
+            note: None,
+        }
+    }
+
+    pub fn builder(
+        account_id: impl Into<AccountId>,
+        amount: Amount,
+    ) -> CreditAccountRequestBuilder {
+        CreditAccountRequestBuilder {
+            request: Self::new(account_id, amount),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CreditAccountRequestBuilder {
+    request: CreditAccountRequest,
+}
+
+impl CreditAccountRequestBuilder {
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.request.note = Some(note.into());
+        self
+    }
+
+    pub fn build(self) -> Result<CreditAccountRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl AccountOperationRequest {
+    pub fn new(account_id: impl Into<AccountId>, amount: Amount) -> Self {
+        Self {
+            account_id: account_id.into(),
+            amount,
+            note: None,
+        }
+    }
+
+    pub fn builder(
+        account_id: impl Into<AccountId>,
+        amount: Amount,
+    ) -> AccountOperationRequestBuilder {
+        AccountOperationRequestBuilder {
+            request: Self::new(account_id, amount),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AccountOperationRequestBuilder {
+    request: AccountOperationRequest,
+}
+
+impl AccountOperationRequestBuilder {
+    pub fn with_note(mut self, note: impl Into<String>) -> Self {
+        self.request.note = Some(note.into());
+        self
+    }
+
+    pub fn build(self) -> Result<AccountOperationRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl GetInstrumentsRequest {
+    pub fn new(account_type: AccountType) -> Self {
+        Self { account_type }
+    }
+}
+
+impl GetGroupsRequest {
+    pub fn new(account_type: AccountType) -> Self {
+        Self { account_type }
+    }
+}
+
+impl GetAssetsRequest {
+    pub fn new(account_type: AccountType) -> Self {
+        Self { account_type }
+    }
+}
+
+impl GetOpenedPositionsRequest {
+    pub fn new(account_type: AccountType) -> Self {
+        Self {
+            account_type,
+            account_id: None,
+        }
+    }
+
+    pub fn builder(account_type: AccountType) -> GetOpenedPositionsRequestBuilder {
+        GetOpenedPositionsRequestBuilder {
+            request: Self::new(account_type),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetOpenedPositionsRequestBuilder {
+    request: GetOpenedPositionsRequest,
+}
+
+impl GetOpenedPositionsRequestBuilder {
+    pub fn with_account_id(mut self, account_id: impl Into<AccountId>) -> Self {
+        self.request.account_id = Some(account_id.into());
+        self
+    }
+
+    pub fn build(self) -> Result<GetOpenedPositionsRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl GetClosedTradesReportRequest {
+    /// The range can be at most 31 days, see get_closed_trades_report_for_range for longer ones.
+    pub fn new(account_type: AccountType, range: ReportDateRange) -> Self {
+        Self {
+            account_ids: None,
+            account_type,
+            start_date_time: range.start,
+            end_date_time: range.end,
+        }
+    }
+
+    pub fn builder(
+        account_type: AccountType,
+        range: ReportDateRange,
+    ) -> GetClosedTradesReportRequestBuilder {
+        GetClosedTradesReportRequestBuilder {
+            request: Self::new(account_type, range),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetClosedTradesReportRequestBuilder {
+    request: GetClosedTradesReportRequest,
+}
+
+impl GetClosedTradesReportRequestBuilder {
+    /// Adds the account to the ones the report is requested for. All accounts by default.
+    pub fn with_account_id(mut self, account_id: impl Into<AccountId>) -> Self {
+        push_account_id(&mut self.request.account_ids, account_id);
+        self
+    }
+
+    pub fn with_account_ids(
+        mut self,
+        account_ids: impl IntoIterator<Item = impl Into<AccountId>>,
+    ) -> Self {
+        self.request.account_ids = collect_account_ids(account_ids);
+        self
+    }
+
+    pub fn build(self) -> Result<GetClosedTradesReportRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl GetClosedPositionsReportRequest {
+    /// The range can be at most 31 days, see get_closed_positions_report_for_range for longer ones.
+    pub fn new(account_type: AccountType, range: ReportDateRange) -> Self {
+        Self {
+            account_ids: None,
+            account_type,
+            start_date_time: range.start,
+            end_date_time: range.end,
+        }
+    }
+
+    pub fn builder(
+        account_type: AccountType,
+        range: ReportDateRange,
+    ) -> GetClosedPositionsReportRequestBuilder {
+        GetClosedPositionsReportRequestBuilder {
+            request: Self::new(account_type, range),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetClosedPositionsReportRequestBuilder {
+    request: GetClosedPositionsReportRequest,
+}
+
+impl GetClosedPositionsReportRequestBuilder {
+    /// Adds the account to the ones the report is requested for. All accounts by default.
+    pub fn with_account_id(mut self, account_id: impl Into<AccountId>) -> Self {
+        push_account_id(&mut self.request.account_ids, account_id);
+        self
+    }
+
+    pub fn with_account_ids(
+        mut self,
+        account_ids: impl IntoIterator<Item = impl Into<AccountId>>,
+    ) -> Self {
+        self.request.account_ids = collect_account_ids(account_ids);
+        self
+    }
+
+    pub fn build(self) -> Result<GetClosedPositionsReportRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl GetTradesReportRequest {
+    pub fn new(account_type: AccountType) -> Self {
+        Self {
+            account_type,
+            account_ids: None,
+            start_date_time: None,
+            end_date_time: None,
+        }
+    }
+
+    pub fn builder(account_type: AccountType) -> GetTradesReportRequestBuilder {
+        GetTradesReportRequestBuilder {
+            request: Self::new(account_type),
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetTradesReportRequestBuilder {
+    request: GetTradesReportRequest,
+    /// Invalid argument of a date helper, returned by build.
+    error: Option<BrandApiValidationError>,
+}
+
+impl GetTradesReportRequestBuilder {
+    /// Adds the account to the ones the report is requested for. All accounts by default.
+    pub fn with_account_id(mut self, account_id: impl Into<AccountId>) -> Self {
+        push_account_id(&mut self.request.account_ids, account_id);
+        self
+    }
+
+    pub fn with_account_ids(
+        mut self,
+        account_ids: impl IntoIterator<Item = impl Into<AccountId>>,
+    ) -> Self {
+        self.request.account_ids = collect_account_ids(account_ids);
+        self
+    }
+
+    pub fn with_date_range(mut self, range: ReportDateRange) -> Self {
+        self.request.start_date_time = Some(range.start);
+        self.request.end_date_time = Some(range.end);
+        self
+    }
+
+    /// Requests the last `days` days up to now.
+    pub fn last_days(self, days: u32) -> Self {
+        self.with_date_range(ReportDateRange::last_days(days))
+    }
+
+    /// Requests the calendar month in UTC. build fails if `month` is not in 1..=12.
+    pub fn month(mut self, year: i32, month: u32) -> Self {
+        match ReportDateRange::month(year, month) {
+            Ok(range) => self.with_date_range(range),
+            Err(err) => {
+                self.error = Some(err);
+                self
+            }
+        }
+    }
+
+    pub fn build(self) -> Result<GetTradesReportRequest, BrandApiValidationError> {
+        match self.error {
+            Some(err) => Err(err),
+            None => build(self.request),
+        }
+    }
+}
+
+impl GetCursorReportRequest {
+    /// Request of the first page.
+    pub fn new(account_type: AccountType) -> Self {
+        Self {
+            account_type,
+            account_id: None,
+            start_date_time: None,
+            end_date_time: None,
+            cursor: get_default_cursor(),
+            limit: None,
+        }
+    }
+
+    pub fn builder(account_type: AccountType) -> GetCursorReportRequestBuilder {
+        GetCursorReportRequestBuilder {
+            request: Self::new(account_type),
+            error: None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetCursorReportRequestBuilder {
+    request: GetCursorReportRequest,
+    /// Invalid argument of a date helper, returned by build.
+    error: Option<BrandApiValidationError>,
+}
+
+impl GetCursorReportRequestBuilder {
+    pub fn with_account_id(mut self, account_id: impl Into<AccountId>) -> Self {
+        self.request.account_id = Some(account_id.into());
+        self
+    }
+
+    pub fn with_date_range(mut self, range: ReportDateRange) -> Self {
+        self.request.start_date_time = Some(range.start);
+        self.request.end_date_time = Some(range.end);
+        self
+    }
+
+    /// Requests the last `days` days up to now.
+    pub fn last_days(self, days: u32) -> Self {
+        self.with_date_range(ReportDateRange::last_days(days))
+    }
+
+    /// Requests the calendar month in UTC. build fails if `month` is not in 1..=12.
+    pub fn month(mut self, year: i32, month: u32) -> Self {
+        match ReportDateRange::month(year, month) {
+            Ok(range) => self.with_date_range(range),
+            Err(err) => {
+                self.error = Some(err);
+                self
+            }
+        }
+    }
+
+    pub fn with_cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.request.cursor = cursor.into();
+        self
+    }
+
+    /// Number of items per page.
+    pub fn with_limit(mut self, limit: u32) -> Self {
+        self.request.limit = Some(limit);
+        self
+    }
+
+    pub fn build(self) -> Result<GetCursorReportRequest, BrandApiValidationError> {
+        match self.error {
+            Some(err) => Err(err),
+            None => build(self.request),
+        }
+    }
+}
+
+impl GetAccountsReportRequest {
+    pub fn new(account_type: AccountType) -> Self {
+        Self {
+            account_type,
+            account_ids: None,
+            account_status: None,
+        }
+    }
+
+    pub fn builder(account_type: AccountType) -> GetAccountsReportRequestBuilder {
+        GetAccountsReportRequestBuilder {
+            request: Self::new(account_type),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetAccountsReportRequestBuilder {
+    request: GetAccountsReportRequest,
+}
+
+impl GetAccountsReportRequestBuilder {
+    /// Adds the account to the ones the report is requested for. All accounts by default.
+    pub fn with_account_id(mut self, account_id: impl Into<AccountId>) -> Self {
+        push_account_id(&mut self.request.account_ids, account_id);
+        self
+    }
+
+    pub fn with_account_ids(
+        mut self,
+        account_ids: impl IntoIterator<Item = impl Into<AccountId>>,
+    ) -> Self {
+        self.request.account_ids = collect_account_ids(account_ids);
+        self
+    }
+
+    pub fn with_account_status(mut self, account_status: AccountStatus) -> Self {
+        self.request.account_status = Some(account_status);
+        self
+    }
+
+    pub fn build(self) -> Result<GetAccountsReportRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl CancelOrderRequest {
+    pub fn new(account_type: AccountType, order_id: impl Into<OrderId>) -> Self {
+        Self {
+            account_type,
+            order_id: order_id.into(),
+        }
+    }
+}
+
+impl GetOrdersRequest {
+    pub fn new(account_type: AccountType) -> Self {
+        Self {
+            account_type,
+            account_id: None,
+            offset: None,
+            limit: None,
+        }
+    }
+
+    pub fn builder(account_type: AccountType) -> GetOrdersRequestBuilder {
+        GetOrdersRequestBuilder {
+            request: Self::new(account_type),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GetOrdersRequestBuilder {
+    request: GetOrdersRequest,
+}
+
+impl GetOrdersRequestBuilder {
+    pub fn with_account_id(mut self, account_id: impl Into<AccountId>) -> Self {
+        self.request.account_id = Some(account_id.into());
+        self
+    }
+
+    pub fn with_offset(mut self, offset: i32) -> Self {
+        self.request.offset = Some(offset);
+        self
+    }
+
+    pub fn with_limit(mut self, limit: i32) -> Self {
+        self.request.limit = Some(limit);
+        self
+    }
+
+    pub fn build(self) -> Result<GetOrdersRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+impl MonthlyActiveAccountsRequest {
+    /// Requests the calendar month in UTC as Json. The month is validated before sending.
+    pub fn new(year: i32, month: u32) -> Self {
+        Self {
+            for_month: format!("{:04}-{:02}", year, month),
+            return_type: ReturnType::Json,
+        }
+    }
+
+    pub fn builder(year: i32, month: u32) -> MonthlyActiveAccountsRequestBuilder {
+        MonthlyActiveAccountsRequestBuilder {
+            request: Self::new(year, month),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct MonthlyActiveAccountsRequestBuilder {
+    request: MonthlyActiveAccountsRequest,
+}
+
+impl MonthlyActiveAccountsRequestBuilder {
+    pub fn with_return_type(mut self, return_type: ReturnType) -> Self {
+        self.request.return_type = return_type;
+        self
+    }
+
+    pub fn build(self) -> Result<MonthlyActiveAccountsRequest, BrandApiValidationError> {
+        build(self.request)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn builds_trades_report_request() {
+        let request = GetTradesReportRequest::builder(AccountType::Live)
+            .with_account_id("L#1")
+            .with_account_id("L#2")
+            .month(2026, 9)
+            .build()
+            .unwrap();
+
+        let json = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(json["type"], "LIVE");
+        assert_eq!(json["accountId"], serde_json::json!(["L#1", "L#2"]));
+        assert_eq!(json["startDateTime"], "2026-09-01T00:00:00.000Z");
+        assert_eq!(json["endDateTime"], "2026-09-30T23:59:59.999Z");
+    }
+
+    #[test]
+    fn skips_unset_optional_fields() {
+        let json = serde_json::to_value(GetOrdersRequest::new(AccountType::Demo)).unwrap();
+
+        assert_eq!(json["type"], "DEMO");
+        assert!(json.get("offset").is_none());
+        assert!(json.get("limit").is_none());
+    }
+
+    #[test]
+    fn builds_last_days_range() {
+        let request = GetCursorReportRequest::builder(AccountType::Live)
+            .last_days(7)
+            .build()
+            .unwrap();
+        let start: DateTime<Utc> = request.start_date_time.unwrap();
+        let end: DateTime<Utc> = request.end_date_time.unwrap();
+
+        assert_eq!(end - start, chrono::TimeDelta::days(7));
+        assert_eq!(request.cursor, get_default_cursor());
+    }
+
+    #[test]
+    fn builds_monthly_active_accounts_request() {
+        let request = MonthlyActiveAccountsRequest::builder(2026, 9)
+            .with_return_type(ReturnType::Csv)
+            .build()
+            .unwrap();
+
+        assert_eq!(request.for_month, "2026-09");
+        assert_eq!(request.return_type, ReturnType::Csv);
+    }
+
+    #[test]
+    fn rejects_invalid_month() {
+        let result = GetTradesReportRequest::builder(AccountType::Live)
+            .month(2026, 13)
+            .build();
+        assert_eq!(
+            result.unwrap_err(),
+            BrandApiValidationError::InvalidMonth("2026-13".to_string())
+        );
+
+        let result = MonthlyActiveAccountsRequest::builder(2026, 0).build();
+        assert_eq!(
+            result.unwrap_err(),
+            BrandApiValidationError::InvalidMonth("2026-00".to_string())
+        );
+    }
+}
@@ -0,0 +1,196 @@
+//! Per-account outcome of the bulk operations of BrandApiClient.
+
+use crate::brand::errors::Error;
+use crate::brand::rate_limiter::RateLimit;
+use crate::models::AccountId;
+use std::num::NonZeroUsize;
+
+/// Controls how fast a bulk operation of BrandApiClient runs.
+#[derive(Debug, Clone, Copy)]
+pub struct BulkOperationOptions {
+    /// Accounts processed at once.
+    pub max_concurrency: NonZeroUsize,
+    /// Budget of this call only, kept apart from other calls. It does not replace the rate
+    /// limiter of the client: when both are set, each request waits for both of them.
+    /// Without it the requests are only bounded by `max_concurrency`.
+    pub rate_limit: Option<RateLimit>,
+}
+
+impl BulkOperationOptions {
+    pub fn new(max_concurrency: NonZeroUsize) -> Self {
+        Self {
+            max_concurrency,
+            rate_limit: None,
+        }
+    }
+
+    pub fn with_rate_limit(mut self, rate_limit: RateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+}
+
+/// Result of an operation run for many accounts. A failure for one account does not stop
+/// the others.
+#[derive(Debug)]
+pub struct BulkOperationReport<T> {
+    /// Results in the order of the requested account ids.
+    pub results: Vec<(AccountId, Result<T, Error>)>,
+}
+
+impl<T> BulkOperationReport<T> {
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|(_, result)| result.is_ok())
+    }
+
+    pub fn get_succeeded(&self) -> impl Iterator<Item = (&AccountId, &T)> {
+        self.results
+            .iter()
+            .filter_map(|(account_id, result)| result.as_ref().ok().map(|data| (account_id, data)))
+    }
+
+    pub fn get_failed(&self) -> impl Iterator<Item = (&AccountId, &Error)> {
+        self.results
+            .iter()
+            .filter_map(|(account_id, result)| result.as_ref().err().map(|err| (account_id, err)))
+    }
+
+    /// Ids of the failed accounts, e.g. to retry only them.
+    pub fn get_failed_account_ids(&self) -> Vec<AccountId> {
+        self.get_failed()
+            .map(|(account_id, _)| account_id.clone())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand::api_client::BrandApiClient;
+    use crate::brand::mock_server::MockBrandApi;
+    use crate::brand::test_support::{create_account, get_client, get_opened_position, TestConfig};
+    use crate::brand::transport::{
+        BrandApiTransport, BrandApiTransportRequest, BrandApiTransportResponse,
+    };
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    /// Forwards to the mock after a delay and records how many requests were in flight at once.
+    struct SlowTransport {
+        inner: Arc<dyn BrandApiTransport + Send + Sync>,
+        in_flight: AtomicUsize,
+        peak: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl BrandApiTransport for SlowTransport {
+        async fn send(
+            &self,
+            request: BrandApiTransportRequest,
+        ) -> Result<BrandApiTransportResponse, String> {
+            let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(in_flight, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            let response = self.inner.send(request).await;
+            self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+            response
+        }
+    }
+
+    fn get_unknown_account_ids(count: usize) -> Vec<AccountId> {
+        (0..count)
+            .map(|i| AccountId::from(format!("L#{}", 1000 + i)))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn reports_bulk_operation_per_account() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let account_id = create_account(&client).await;
+        let account_ids = vec![account_id.clone(), AccountId::from("L#404")];
+
+        let options = BulkOperationOptions::new(NonZeroUsize::new(4).unwrap());
+
+        let report = client.suspend_accounts(&account_ids, options).await;
+
+        assert!(!report.is_success());
+        assert_eq!(report.get_succeeded().count(), 1);
+        assert_eq!(
+            report.get_failed_account_ids(),
+            vec![AccountId::from("L#404")]
+        );
+        assert_eq!(report.results[0].0, account_id);
+        assert_eq!(api.get_status(&account_id).as_deref(), Some("SUSPENDED"));
+
+        let report = client
+            .set_accounts_group(&account_ids[..1], &"1".into(), options)
+            .await;
+        assert!(report.is_success());
+    }
+
+    #[tokio::test]
+    async fn closes_positions_of_accounts() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let account_id = create_account(&client).await;
+        api.add_opened_position(&get_opened_position("1", &account_id));
+        let account_ids = vec![AccountId::from("L#404"), account_id.clone()];
+        let options = BulkOperationOptions::new(NonZeroUsize::new(1).unwrap())
+            .with_rate_limit(RateLimit::per_second(100));
+
+        let report = client.close_accounts_positions(&account_ids, options).await;
+
+        assert_eq!(
+            report.get_failed_account_ids(),
+            vec![AccountId::from("L#404")]
+        );
+        let succeeded: Vec<_> = report.get_succeeded().collect();
+        assert_eq!(succeeded.len(), 1);
+        assert_eq!(succeeded[0].0, &account_id);
+        assert_eq!(succeeded[0].1.position_ids.len(), 1);
+        assert_eq!(succeeded[0].1.position_ids[0].as_str(), "1");
+        assert_eq!(api.get_opened_positions_count(&account_id), 0);
+    }
+
+    #[tokio::test]
+    async fn limits_concurrent_requests() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let transport = Arc::new(SlowTransport {
+            inner: api.get_transport(),
+            in_flight: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+        });
+        let client = BrandApiClient::new(TestConfig).with_transport(transport.clone());
+        let account_ids = get_unknown_account_ids(6);
+        let options = BulkOperationOptions::new(NonZeroUsize::new(2).unwrap());
+
+        let report = client.suspend_accounts(&account_ids, options).await;
+
+        assert_eq!(report.get_failed_account_ids(), account_ids);
+        assert_eq!(api.get_requests_count(), 6);
+        assert_eq!(transport.peak.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn limits_request_rate() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let account_ids = get_unknown_account_ids(4);
+        // The bucket starts with one request, the next ones wait 50ms each.
+        let rate_limit = RateLimit {
+            requests: 1,
+            per: Duration::from_millis(50),
+        };
+        let options =
+            BulkOperationOptions::new(NonZeroUsize::new(4).unwrap()).with_rate_limit(rate_limit);
+        let started = Instant::now();
+
+        let report = client.suspend_accounts(&account_ids, options).await;
+
+        assert_eq!(report.get_failed_account_ids(), account_ids);
+        assert!(started.elapsed() >= Duration::from_millis(140));
+    }
+}
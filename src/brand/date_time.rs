@@ -0,0 +1,186 @@
+//! Serde helpers for Brand API timestamps.
+//!
+//! The api returns dates as ISO strings in some reports and as epoch milliseconds
+//! (numbers or strings) in others. Use with `#[serde(with = "crate::brand::date_time")]`,
+//! `#[serde(with = "crate::brand::date_time::option")]` for optional fields
+//! or `#[serde(with = "crate::brand::date_time::millis")]` for fields sent as milliseconds.
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use serde::{Deserialize, Deserializer, Serializer};
+
+/// Format expected by the api: yyyy-MM-ddTHH:mm:ss.SSSZ e.g., 2021-12-31T23:59:59.999Z
+const FORMAT: &str = "%Y-%m-%dT%H:%M:%S%.3fZ";
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDateTime {
+    Millis(i64),
+    Text(String),
+}
+
+impl RawDateTime {
+    fn into_date_time<E: serde::de::Error>(self) -> Result<DateTime<Utc>, E> {
+        match self {
+            RawDateTime::Millis(millis) => DateTime::from_timestamp_millis(millis)
+                .ok_or_else(|| E::custom(format!("timestamp is out of range: {}", millis))),
+            RawDateTime::Text(text) => parse_date_time(&text)
+                .ok_or_else(|| E::custom(format!("invalid date time: {:?}", text))),
+        }
+    }
+}
+
+/// Parses an ISO date time or epoch milliseconds. ISO values without offset are treated as UTC.
+pub fn parse_date_time(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+
+    if let Ok(millis) = value.parse::<i64>() {
+        return DateTime::from_timestamp_millis(millis);
+    }
+
+    if let Ok(date_time) = DateTime::parse_from_rfc3339(value) {
+        return Some(date_time.with_timezone(&Utc));
+    }
+
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(value, format).ok())
+        .map(|date_time| date_time.and_utc())
+}
+
+/// Formats the date time the way the api expects it in requests.
+pub fn format_date_time(date_time: &DateTime<Utc>) -> String {
+    date_time.format(FORMAT).to_string()
+}
+
+pub fn serialize<S: Serializer>(date_time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_date_time(date_time))
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<DateTime<Utc>, D::Error> {
+    RawDateTime::deserialize(deserializer)?.into_date_time()
+}
+
+pub mod option {
+    use super::RawDateTime;
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        date_time: &Option<DateTime<Utc>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        match date_time {
+            Some(date_time) => super::serialize(date_time, serializer),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<DateTime<Utc>>, D::Error> {
+        match Option::<RawDateTime>::deserialize(deserializer)? {
+            Some(RawDateTime::Text(text)) if text.is_empty() => Ok(None),
+            Some(raw) => raw.into_date_time().map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Keeps the epoch milliseconds wire format when serializing, e.g. "1640995199999".
+pub mod millis {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        date_time: &DateTime<Utc>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&date_time.timestamp_millis().to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<DateTime<Utc>, D::Error> {
+        super::deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize, Deserialize)]
+    struct Model {
+        #[serde(with = "crate::brand::date_time")]
+        date: DateTime<Utc>,
+        #[serde(default, with = "crate::brand::date_time::option")]
+        optional: Option<DateTime<Utc>>,
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct MillisModel {
+        #[serde(with = "crate::brand::date_time::millis")]
+        date: DateTime<Utc>,
+    }
+
+    #[test]
+    fn parses_iso_and_millis() {
+        let expected = DateTime::from_timestamp_millis(1640995199999).unwrap();
+
+        for json in [
+            r#"{"date":"2021-12-31T23:59:59.999Z"}"#,
+            r#"{"date":"2021-12-31T23:59:59.999"}"#,
+            r#"{"date":"2022-01-01T01:59:59.999+02:00"}"#,
+            r#"{"date":"1640995199999"}"#,
+            r#"{"date":1640995199999}"#,
+        ] {
+            let model: Model = serde_json::from_str(json).unwrap();
+
+            assert_eq!(model.date, expected, "{}", json);
+            assert!(model.optional.is_none());
+        }
+    }
+
+    #[test]
+    fn parses_optional() {
+        let model: Model =
+            serde_json::from_str(r#"{"date":1640995199999,"optional":null}"#).unwrap();
+        assert!(model.optional.is_none());
+
+        let model: Model =
+            serde_json::from_str(r#"{"date":1640995199999,"optional":""}"#).unwrap();
+        assert!(model.optional.is_none());
+
+        let model: Model =
+            serde_json::from_str(r#"{"date":1640995199999,"optional":"1640995199999"}"#).unwrap();
+        assert_eq!(model.optional, Some(model.date));
+    }
+
+    #[test]
+    fn rejects_invalid() {
+        assert!(serde_json::from_str::<Model>(r#"{"date":"yesterday"}"#).is_err());
+    }
+
+    #[test]
+    fn serializes_in_api_format() {
+        let model = Model {
+            date: DateTime::from_timestamp_millis(1640995199999).unwrap(),
+            optional: None,
+        };
+
+        assert_eq!(
+            serde_json::to_string(&model).unwrap(),
+            r#"{"date":"2021-12-31T23:59:59.999Z","optional":null}"#
+        );
+    }
+
+    #[test]
+    fn keeps_millis_format() {
+        let json = r#"{"date":"1640995199999"}"#;
+        let model: MillisModel = serde_json::from_str(json).unwrap();
+
+        assert_eq!(model.date.timestamp_millis(), 1640995199999);
+        assert_eq!(serde_json::to_string(&model).unwrap(), json);
+    }
+}
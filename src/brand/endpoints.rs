@@ -1,6 +1,6 @@
 use http::Method;
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, strum::EnumIter)]
 pub enum BrandApiEndpoint {
     CreateUser,
     CheckEmail,
@@ -28,6 +28,10 @@ pub enum BrandApiEndpoint {
     Withdraw,
     MonthlyActiveAccounts,
     GetClosedPositionsHistoryReport,
+    /// v1 closed trades report paginated with a cursor.
+    GetClosedTradesHistoryReportV1,
+    /// v1 trades report paginated with a cursor.
+    GetTradesHistoryReportV1,
 }
 
 impl From<&BrandApiEndpoint> for String {
@@ -114,6 +118,12 @@ impl From<&BrandApiEndpoint> for String {
             BrandApiEndpoint::GetClosedPositionsHistoryReport => {
                 format!("/{api_name}/{api_version}/reports/closed-positions-history-report")
             }
+            BrandApiEndpoint::GetClosedTradesHistoryReportV1 => {
+                format!("/{api_name}/{api_version}/reports/close-trades-history-report")
+            }
+            BrandApiEndpoint::GetTradesHistoryReportV1 => {
+                format!("/{api_name}/{api_version}/reports/trades-history-report")
+            }
         }
     }
 }
@@ -147,6 +157,43 @@ impl BrandApiEndpoint {
             BrandApiEndpoint::Withdraw => Method::POST,
             BrandApiEndpoint::MonthlyActiveAccounts => Method::POST,
             BrandApiEndpoint::GetClosedPositionsHistoryReport => Method::POST,
+            BrandApiEndpoint::GetClosedTradesHistoryReportV1 => Method::POST,
+            BrandApiEndpoint::GetTradesHistoryReportV1 => Method::POST,
+        }
+    }
+
+    /// Whether sending the same request twice has the same effect as sending it once.
+    /// Non-idempotent endpoints are safe to retry only with an Idempotency-Key.
+    pub fn is_idempotent(&self) -> bool {
+        match &self {
+            BrandApiEndpoint::CreateUser => false,
+            BrandApiEndpoint::CheckEmail => true,
+            BrandApiEndpoint::SetUserPassword => true,
+            BrandApiEndpoint::GetAccount => true,
+            BrandApiEndpoint::CreateAccount => false,
+            BrandApiEndpoint::ActivateAccount => true,
+            BrandApiEndpoint::RestrictAccount => true,
+            BrandApiEndpoint::SuspendAccount => true,
+            BrandApiEndpoint::SetAccountGroup => true,
+            BrandApiEndpoint::CloseAccountPositions => false,
+            BrandApiEndpoint::CreditAccount => false,
+            BrandApiEndpoint::GetInstruments => true,
+            BrandApiEndpoint::GetOpenedPositions => true,
+            BrandApiEndpoint::GetClosedTradesHistoryReport => true,
+            BrandApiEndpoint::GetGroups => true,
+            BrandApiEndpoint::GetAccountsReport => true,
+            BrandApiEndpoint::GetApiStatus => true,
+            BrandApiEndpoint::IsApiAlive => true,
+            BrandApiEndpoint::GetTradesHistoryReport => true,
+            BrandApiEndpoint::GetAssets => true,
+            BrandApiEndpoint::GetOrders => true,
+            BrandApiEndpoint::CancelOrder => true,
+            BrandApiEndpoint::Deposit => false,
+            BrandApiEndpoint::Withdraw => false,
+            BrandApiEndpoint::MonthlyActiveAccounts => true,
+            BrandApiEndpoint::GetClosedPositionsHistoryReport => true,
+            BrandApiEndpoint::GetClosedTradesHistoryReportV1 => true,
+            BrandApiEndpoint::GetTradesHistoryReportV1 => true,
         }
     }
 }
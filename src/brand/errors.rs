@@ -1,9 +1,22 @@
+use crate::brand::endpoints::BrandApiEndpoint;
+use chrono::{DateTime, TimeDelta, Utc};
 use error_chain::error_chain;
+use http::Method;
 use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
 
 error_chain! {
     errors {
        RestError(response: String)
+       Api(err: Box<BrandApiError>) {
+           description("brand api error")
+           display("{}", err)
+       }
+       Validation(err: BrandApiValidationError) {
+           description("invalid brand api request")
+           display("Invalid request: {}", err)
+       }
     }
     types {
         Error, ErrorKind, ResultExt, Result;
@@ -17,9 +30,415 @@ error_chain! {
     }
 }
 
-#[derive(strum::Display, Debug, Clone, Serialize, Deserialize)]
+impl Error {
+    /// Returns the typed api error if the failure happened while calling the Brand API.
+    pub fn as_api_error(&self) -> Option<&BrandApiError> {
+        match self.kind() {
+            ErrorKind::Api(err) => Some(err),
+            _ => None,
+        }
+    }
+
+    /// Returns the validation error if the request was rejected before being sent.
+    pub fn as_validation_error(&self) -> Option<&BrandApiValidationError> {
+        match self.kind() {
+            ErrorKind::Validation(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<BrandApiValidationError> for Error {
+    fn from(err: BrandApiValidationError) -> Self {
+        ErrorKind::Validation(err).into()
+    }
+}
+
+impl From<BrandApiError> for Error {
+    fn from(err: BrandApiError) -> Self {
+        ErrorKind::Api(Box::new(err)).into()
+    }
+}
+
+/// Error envelope returned by the Brand API on non-2xx responses.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BrandApiErrorResponse {
+    #[serde(default)]
+    pub code: Option<WebservicesErrorCode>,
+    #[serde(default)]
+    pub message: Option<BrandApiErrorMessage>,
+    #[serde(rename = "statusCode")]
+    #[serde(default)]
+    pub status_code: Option<u16>,
+    /// Http reason phrase, e.g. "Bad Request".
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+impl BrandApiErrorResponse {
+    pub fn parse(body: &str) -> Option<Self> {
+        serde_json::from_str(body).ok()
+    }
+}
+
+/// Validation errors are returned as a list of messages, other errors as a single one.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum BrandApiErrorMessage {
+    Single(String),
+    Multiple(Vec<String>),
+}
+
+impl fmt::Display for BrandApiErrorMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BrandApiErrorMessage::Single(message) => write!(f, "{}", message),
+            BrandApiErrorMessage::Multiple(messages) => write!(f, "{}", messages.join("; ")),
+        }
+    }
+}
+
+/// Extracts the TradeLocker error code from an error response body if it has one.
+pub fn parse_error_code(body: &str) -> Option<WebservicesErrorCode> {
+    BrandApiErrorResponse::parse(body).and_then(|response| response.code)
+}
+
+#[derive(
+    strum::Display, strum::EnumString, Debug, Clone, PartialEq, Eq, Serialize, Deserialize,
+)]
+#[serde(from = "String", into = "String")]
 pub enum WebservicesErrorCode {
     #[strum(to_string = "TRADER_NOT_FOUND")]
-    #[serde(rename = "TRADER_NOT_FOUND")]
     TraderNotFound,
+    #[strum(to_string = "USER_NOT_FOUND")]
+    UserNotFound,
+    #[strum(to_string = "ACCOUNT_NOT_FOUND")]
+    AccountNotFound,
+    #[strum(to_string = "GROUP_NOT_FOUND")]
+    GroupNotFound,
+    #[strum(to_string = "ORDER_NOT_FOUND")]
+    OrderNotFound,
+    #[strum(to_string = "POSITION_NOT_FOUND")]
+    PositionNotFound,
+    #[strum(to_string = "INSUFFICIENT_FUNDS")]
+    InsufficientFunds,
+    #[strum(to_string = "DUPLICATE_EMAIL")]
+    DuplicateEmail,
+    #[strum(to_string = "INVALID_EMAIL")]
+    InvalidEmail,
+    #[strum(to_string = "INVALID_PASSWORD")]
+    InvalidPassword,
+    #[strum(to_string = "INVALID_GROUP")]
+    InvalidGroup,
+    #[strum(to_string = "INVALID_CURRENCY")]
+    InvalidCurrency,
+    #[strum(to_string = "INVALID_AMOUNT")]
+    InvalidAmount,
+    #[strum(to_string = "INVALID_ACCOUNT_TYPE")]
+    InvalidAccountType,
+    #[strum(to_string = "INVALID_ACCOUNT_STATUS")]
+    InvalidAccountStatus,
+    #[strum(to_string = "ACCOUNT_SUSPENDED")]
+    AccountSuspended,
+    #[strum(to_string = "ACCOUNT_RESTRICTED")]
+    AccountRestricted,
+    #[strum(to_string = "IDEMPOTENCY_CONFLICT")]
+    IdempotencyConflict,
+    #[strum(to_string = "VALIDATION_ERROR")]
+    ValidationError,
+    #[strum(to_string = "UNAUTHORIZED")]
+    Unauthorized,
+    #[strum(to_string = "FORBIDDEN")]
+    Forbidden,
+    #[strum(to_string = "RATE_LIMIT_EXCEEDED")]
+    RateLimitExceeded,
+    #[strum(to_string = "INTERNAL_ERROR")]
+    InternalError,
+    #[strum(to_string = "SERVICE_UNAVAILABLE")]
+    ServiceUnavailable,
+    /// Code not known to this client. Holds the raw code.
+    #[strum(default)]
+    Unknown(String),
+}
+
+impl From<String> for WebservicesErrorCode {
+    fn from(code: String) -> Self {
+        code.parse().unwrap_or(WebservicesErrorCode::Unknown(code))
+    }
+}
+
+impl From<WebservicesErrorCode> for String {
+    fn from(code: WebservicesErrorCode) -> Self {
+        code.to_string()
+    }
+}
+
+/// Context of a failed Brand API call.
+#[derive(Debug, Clone)]
+pub struct BrandApiErrorDetails {
+    pub endpoint: BrandApiEndpoint,
+    pub method: Method,
+    pub url: String,
+    /// Serialized request body.
+    pub request: Option<String>,
+    /// Idempotency-Key the request was sent with. Replay it to safely repeat the operation.
+    pub idempotency_key: Option<String>,
+    /// Http status code. None if no response was received.
+    pub status_code: Option<u16>,
+    /// Raw response body. None if no response was received.
+    pub body: Option<String>,
+    /// TradeLocker error code parsed from the response body.
+    pub error_code: Option<WebservicesErrorCode>,
+    /// Delay requested by the Retry-After header of the response.
+    pub retry_after: Option<Duration>,
+    /// Parsed error envelope of the response body.
+    pub error_response: Option<BrandApiErrorResponse>,
+    /// Human readable reason: transport or serde error, or the response message.
+    pub message: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum BrandApiError {
+    /// No response was received within the configured timeout.
+    Timeout(BrandApiErrorDetails),
+    /// The request failed before a response was received.
+    Transport(BrandApiErrorDetails),
+    /// 401 or 403.
+    Unauthorized(BrandApiErrorDetails),
+    /// 400 and other 4xx statuses without a dedicated variant.
+    BadRequest(BrandApiErrorDetails),
+    /// 404.
+    NotFound(BrandApiErrorDetails),
+    /// 429.
+    RateLimited(BrandApiErrorDetails),
+    /// 5xx.
+    ServerError(BrandApiErrorDetails),
+    /// The response was received but its body does not match the expected model.
+    Deserialization(BrandApiErrorDetails),
+}
+
+impl BrandApiError {
+    /// Maps a non-successful http status to the matching variant.
+    pub fn from_status(status_code: u16, details: BrandApiErrorDetails) -> Self {
+        match status_code {
+            401 | 403 => Self::Unauthorized(details),
+            404 => Self::NotFound(details),
+            429 => Self::RateLimited(details),
+            500..=599 => Self::ServerError(details),
+            _ => Self::BadRequest(details),
+        }
+    }
+
+    pub fn details(&self) -> &BrandApiErrorDetails {
+        match self {
+            Self::Timeout(details)
+            | Self::Transport(details)
+            | Self::Unauthorized(details)
+            | Self::BadRequest(details)
+            | Self::NotFound(details)
+            | Self::RateLimited(details)
+            | Self::ServerError(details)
+            | Self::Deserialization(details) => details,
+        }
+    }
+
+    pub fn endpoint(&self) -> BrandApiEndpoint {
+        self.details().endpoint
+    }
+
+    pub fn status_code(&self) -> Option<u16> {
+        self.details().status_code
+    }
+
+    pub fn body(&self) -> Option<&str> {
+        self.details().body.as_deref()
+    }
+
+    pub fn error_code(&self) -> Option<&WebservicesErrorCode> {
+        self.details().error_code.as_ref()
+    }
+
+    fn get_name(&self) -> &'static str {
+        match self {
+            Self::Timeout(_) => "Timeout",
+            Self::Transport(_) => "Transport failure",
+            Self::Unauthorized(_) => "Unauthorized or forbidden",
+            Self::BadRequest(_) => "Bad request",
+            Self::NotFound(_) => "Not found",
+            Self::RateLimited(_) => "Rate limited",
+            Self::ServerError(_) => "Server error",
+            Self::Deserialization(_) => "Failed to deserialize",
+        }
+    }
+}
+
+impl fmt::Display for BrandApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let details = self.details();
+        write!(
+            f,
+            "{}. Url: {:?} {}.",
+            self.get_name(),
+            details.method,
+            details.url
+        )?;
+
+        if let Some(request) = &details.request {
+            write!(f, " Request: {}.", request)?;
+        }
+
+        if let Some(status_code) = details.status_code {
+            write!(f, " Status: {}.", status_code)?;
+        }
+
+        if let Some(error_code) = &details.error_code {
+            write!(f, " Code: {}.", error_code)?;
+        }
+
+        if !details.message.is_empty() {
+            write!(f, " {}.", details.message)?;
+        }
+
+        if let Some(body) = &details.body {
+            write!(f, " Response: {}", body)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Request violates a documented Brand API constraint. Returned without sending the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrandApiValidationError {
+    EmptyAccountId,
+    /// Deposit and withdrawal amounts must be greater than zero.
+    NonPositiveAmount(String),
+    /// Credit amount can be negative but not zero.
+    ZeroAmount,
+    /// Start date is after end date.
+    InvalidDateRange {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    },
+    /// Range between start and end date is longer than allowed by the endpoint.
+    DateRangeTooLong {
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        max: TimeDelta,
+    },
+    /// Currency is not an ISO 4217 code.
+    InvalidCurrency(String),
+    InvalidEmail(String),
+    /// Month is not a YYYY-MM month.
+    InvalidMonth(String),
+}
+
+impl fmt::Display for BrandApiValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptyAccountId => write!(f, "account id is empty"),
+            Self::NonPositiveAmount(amount) => write!(f, "amount must be positive: {}", amount),
+            Self::ZeroAmount => write!(f, "amount must not be zero"),
+            Self::InvalidDateRange { start, end } => {
+                write!(f, "start date {} is after end date {}", start, end)
+            }
+            Self::DateRangeTooLong { start, end, max } => write!(
+                f,
+                "range from {} to {} is longer than {} days",
+                start,
+                end,
+                max.num_days()
+            ),
+            Self::InvalidCurrency(currency) => {
+                write!(f, "currency is not an ISO 4217 code: {:?}", currency)
+            }
+            Self::InvalidEmail(email) => write!(f, "email is malformed: {:?}", email),
+            Self::InvalidMonth(month) => write!(f, "month is not a YYYY-MM month: {:?}", month),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_details(status_code: u16, body: &str) -> BrandApiErrorDetails {
+        BrandApiErrorDetails {
+            endpoint: BrandApiEndpoint::GetAccount,
+            method: Method::POST,
+            url: "http://localhost/brand-api/v1/accounts/details".to_string(),
+            request: None,
+            idempotency_key: None,
+            status_code: Some(status_code),
+            body: Some(body.to_string()),
+            error_code: parse_error_code(body),
+            retry_after: None,
+            error_response: BrandApiErrorResponse::parse(body),
+            message: String::new(),
+        }
+    }
+
+    #[test]
+    fn maps_status_to_variant() {
+        let err = BrandApiError::from_status(401, get_details(401, ""));
+        assert!(matches!(err, BrandApiError::Unauthorized(_)));
+
+        let err = BrandApiError::from_status(404, get_details(404, ""));
+        assert!(matches!(err, BrandApiError::NotFound(_)));
+
+        let err = BrandApiError::from_status(429, get_details(429, ""));
+        assert!(matches!(err, BrandApiError::RateLimited(_)));
+
+        let err = BrandApiError::from_status(503, get_details(503, ""));
+        assert!(matches!(err, BrandApiError::ServerError(_)));
+
+        let err = BrandApiError::from_status(422, get_details(422, ""));
+        assert!(matches!(err, BrandApiError::BadRequest(_)));
+    }
+
+    #[test]
+    fn parses_error_code() {
+        let details = get_details(404, r#"{"code":"TRADER_NOT_FOUND","message":"not found"}"#);
+        assert!(matches!(
+            details.error_code,
+            Some(WebservicesErrorCode::TraderNotFound)
+        ));
+
+        let details = get_details(500, "Internal Server Error");
+        assert!(details.error_code.is_none());
+    }
+
+    #[test]
+    fn falls_back_to_unknown_error_code() {
+        let details = get_details(400, r#"{"code":"SOMETHING_NEW","message":["a","b"]}"#);
+
+        assert_eq!(
+            details.error_code,
+            Some(WebservicesErrorCode::Unknown("SOMETHING_NEW".to_string()))
+        );
+        assert_eq!(details.error_code.unwrap().to_string(), "SOMETHING_NEW");
+        assert_eq!(
+            details.error_response.unwrap().message.unwrap().to_string(),
+            "a; b"
+        );
+    }
+
+    #[test]
+    fn serializes_error_code() {
+        let code = WebservicesErrorCode::InsufficientFunds;
+        let json = serde_json::to_string(&code).unwrap();
+
+        assert_eq!(json, r#""INSUFFICIENT_FUNDS""#);
+        assert_eq!(
+            serde_json::from_str::<WebservicesErrorCode>(&json).unwrap(),
+            code
+        );
+    }
+
+    #[test]
+    fn exposes_api_error() {
+        let err: Error = BrandApiError::from_status(404, get_details(404, "")).into();
+
+        assert_eq!(err.as_api_error().unwrap().status_code(), Some(404));
+    }
 }
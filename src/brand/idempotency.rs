@@ -0,0 +1,89 @@
+use crate::brand::endpoints::BrandApiEndpoint;
+use serde_derive::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Namespace for keys derived from business ids. Must never change,
+/// otherwise replays after an update would not be deduplicated.
+const IDEMPOTENCY_KEY_NAMESPACE: Uuid = Uuid::from_u128(0x6f1c_2f4e_8a3b_4d57_9c1e_5b7a_0d2e_4f81);
+
+/// Value of the Idempotency-Key header. Sending the same key twice makes the Brand API
+/// apply the operation only once.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IdempotencyKey(String);
+
+impl IdempotencyKey {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self(key.into())
+    }
+
+    /// Random UUID v4 key.
+    pub fn generate() -> Self {
+        Self(Uuid::new_v4().to_string())
+    }
+
+    /// Deterministic UUID v5 key: the same endpoint and business id always produce the same key,
+    /// so an operation replayed after a crash is not applied twice.
+    pub fn derive(endpoint: BrandApiEndpoint, business_id: &str) -> Self {
+        let name = format!("{}:{}", String::from(&endpoint), business_id);
+
+        Self(Uuid::new_v5(&IDEMPOTENCY_KEY_NAMESPACE, name.as_bytes()).to_string())
+    }
+
+    /// Derives the key from `business_id` if provided, otherwise generates a new one.
+    pub fn resolve(endpoint: BrandApiEndpoint, business_id: Option<&str>) -> Self {
+        match business_id {
+            Some(business_id) => Self::derive(endpoint, business_id),
+            None => Self::generate(),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for IdempotencyKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Response of a money-moving call together with the Idempotency-Key it was sent with.
+#[derive(Debug, Clone)]
+pub struct IdempotentResponse<T> {
+    pub idempotency_key: IdempotencyKey,
+    pub data: T,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derives_same_key_for_same_business_id() {
+        let first = IdempotencyKey::derive(BrandApiEndpoint::Deposit, "payout-42");
+        let second = IdempotencyKey::derive(BrandApiEndpoint::Deposit, "payout-42");
+
+        assert_eq!(first, second);
+        assert!(Uuid::parse_str(first.as_str()).is_ok());
+    }
+
+    #[test]
+    fn derives_different_keys_per_endpoint() {
+        let deposit = IdempotencyKey::derive(BrandApiEndpoint::Deposit, "payout-42");
+        let credit = IdempotencyKey::derive(BrandApiEndpoint::CreditAccount, "payout-42");
+
+        assert_ne!(deposit, credit);
+    }
+
+    #[test]
+    fn generates_unique_keys() {
+        assert_ne!(IdempotencyKey::generate(), IdempotencyKey::generate());
+        assert_ne!(
+            IdempotencyKey::resolve(BrandApiEndpoint::Deposit, None),
+            IdempotencyKey::resolve(BrandApiEndpoint::Deposit, None)
+        );
+    }
+}
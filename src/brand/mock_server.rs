@@ -0,0 +1,1089 @@
+//! In-memory imitation of the Brand API for tests. Enabled with the `mock-server` feature.
+//!
+//! `MockBrandApi` keeps users, accounts and balances in memory and serves positions, orders
+//! and reports from seeded fixtures. Use `MockBrandApi::get_transport` to plug it into
+//! BrandApiClient directly or `MockBrandApi::serve` to run it as a local http server.
+
+use crate::brand::date_time::{format_date_time, parse_date_time};
+use crate::brand::endpoints::BrandApiEndpoint;
+use crate::brand::report_range::MAX_REPORT_WINDOW;
+use crate::brand::transport::{
+    BrandApiTransport, BrandApiTransportRequest, BrandApiTransportResponse,
+};
+use crate::brand::{
+    get_default_cursor, AssetModel, ClosedPositionModel, ClosedTradeReportModel, GroupModel, InstrumentModel,
+    MonthlyActiveAccountModel, OpenedPositionModel, OrderModel, TradeReportModel,
+};
+use crate::models::AccountId;
+use chrono::{DateTime, Utc};
+use http::Method;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use strum::IntoEnumIterator;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+struct MockUser {
+    user_id: String,
+    email: String,
+}
+
+struct MockAccount {
+    account_id: String,
+    account_name: String,
+    user_id: String,
+    group_id: String,
+    account_type: String,
+    status: String,
+    currency: String,
+    balance: f64,
+    credit: f64,
+    created_date_time: String,
+}
+
+impl MockAccount {
+    fn to_json(&self) -> Value {
+        json!({
+            "accountName": self.account_name,
+            "accountId": self.account_id,
+            "userId": self.user_id,
+            "userGroupId": self.group_id,
+            "type": self.account_type,
+            "status": self.status,
+            "tradingDisabledReason": null,
+            "currency": self.currency,
+            "leverage": null,
+            "balance": format_amount(self.balance),
+            "credit": format_amount(self.credit),
+            "equity": format_amount(self.balance + self.credit),
+            "pnl": "0.00",
+            "marginAvailable": format_amount(self.balance + self.credit),
+            "marginUsed": "0.00",
+            "createdDateTime": self.created_date_time,
+        })
+    }
+
+    fn to_report_json(&self) -> Value {
+        json!({
+            "accountId": self.account_id,
+            "balance": format_amount(self.balance),
+            "credit": format_amount(self.credit),
+            "equity": format_amount(self.balance + self.credit),
+            "pnl": "0.00",
+            "marginUsed": "0.00",
+            "marginAvailable": format_amount(self.balance + self.credit),
+            "userGroupId": self.group_id,
+        })
+    }
+}
+
+struct MockBrandApiState {
+    users: Vec<MockUser>,
+    accounts: Vec<MockAccount>,
+    default_group_id: String,
+    groups: Vec<Value>,
+    instruments: Vec<Value>,
+    assets: Vec<Value>,
+    opened_positions: Vec<Value>,
+    orders: Vec<Value>,
+    closed_trades: Vec<Value>,
+    closed_positions: Vec<Value>,
+    trades: Vec<Value>,
+    monthly_active_accounts: Vec<Value>,
+    /// Max number of orders returned per page, whatever the requested limit.
+    max_orders_page_size: usize,
+    /// Responses of calls made with an Idempotency-Key: (endpoint, key) -> (request, response).
+    idempotent_responses: HashMap<(BrandApiEndpoint, String), (Vec<u8>, MockResponse)>,
+    next_id: u64,
+}
+
+impl MockBrandApiState {
+    fn get_next_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    fn find_account(&mut self, account_id: &str) -> Result<&mut MockAccount, MockResponse> {
+        self.accounts
+            .iter_mut()
+            .find(|account| account.account_id == account_id)
+            .ok_or_else(|| {
+                MockResponse::error(404, "ACCOUNT_NOT_FOUND", "Account not found")
+            })
+    }
+}
+
+#[derive(Clone)]
+struct MockResponse {
+    status_code: u16,
+    body: String,
+}
+
+impl MockResponse {
+    fn ok(body: Value) -> Self {
+        Self {
+            status_code: 200,
+            body: body.to_string(),
+        }
+    }
+
+    fn empty() -> Self {
+        Self {
+            status_code: 204,
+            body: String::new(),
+        }
+    }
+
+    fn error(status_code: u16, code: &str, message: &str) -> Self {
+        let error = http::StatusCode::from_u16(status_code)
+            .ok()
+            .and_then(|status| status.canonical_reason())
+            .unwrap_or_default();
+
+        Self {
+            status_code,
+            body: json!({
+                "statusCode": status_code,
+                "code": code,
+                "message": message,
+                "error": error,
+            })
+            .to_string(),
+        }
+    }
+}
+
+pub struct MockBrandApi {
+    api_key: String,
+    state: Mutex<MockBrandApiState>,
+    requests_count: AtomicUsize,
+}
+
+impl MockBrandApi {
+    /// Requests must carry `api_key` in the brand-api-key header.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        let default_group_id = "1".to_string();
+
+        Self {
+            api_key: api_key.into(),
+            state: Mutex::new(MockBrandApiState {
+                users: vec![],
+                accounts: vec![],
+                groups: vec![json!({ "name": "default", "id": default_group_id })],
+                default_group_id,
+                instruments: vec![],
+                assets: vec![],
+                opened_positions: vec![],
+                orders: vec![],
+                closed_trades: vec![],
+                closed_positions: vec![],
+                trades: vec![],
+                monthly_active_accounts: vec![],
+                max_orders_page_size: usize::MAX,
+                idempotent_responses: HashMap::new(),
+                next_id: 700000,
+            }),
+            requests_count: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn add_group(&self, group: &GroupModel) {
+        self.state.lock().unwrap().groups.push(to_json(group));
+    }
+
+    pub fn add_instrument(&self, instrument: &InstrumentModel) {
+        self.state.lock().unwrap().instruments.push(to_json(instrument));
+    }
+
+    pub fn add_asset(&self, asset: &AssetModel) {
+        self.state.lock().unwrap().assets.push(to_json(asset));
+    }
+
+    pub fn add_opened_position(&self, position: &OpenedPositionModel) {
+        self.state
+            .lock()
+            .unwrap()
+            .opened_positions
+            .push(to_json(position));
+    }
+
+    pub fn add_order(&self, order: &OrderModel) {
+        self.state.lock().unwrap().orders.push(to_json(order));
+    }
+
+    /// Returns at most `max_page_size` orders per page, like a server capping the requested limit.
+    pub fn set_max_orders_page_size(&self, max_page_size: usize) {
+        self.state.lock().unwrap().max_orders_page_size = max_page_size;
+    }
+
+    pub fn add_closed_trade(&self, trade: &ClosedTradeReportModel) {
+        self.state.lock().unwrap().closed_trades.push(to_json(trade));
+    }
+
+    pub fn add_closed_position(&self, position: &ClosedPositionModel) {
+        self.state
+            .lock()
+            .unwrap()
+            .closed_positions
+            .push(to_json(position));
+    }
+
+    pub fn add_trade(&self, trade: &TradeReportModel) {
+        self.state.lock().unwrap().trades.push(to_json(trade));
+    }
+
+    pub fn add_monthly_active_account(&self, account: &MonthlyActiveAccountModel) {
+        self.state
+            .lock()
+            .unwrap()
+            .monthly_active_accounts
+            .push(to_json(account));
+    }
+
+    /// Current balance of the account, None if it does not exist.
+    pub fn get_balance(&self, account_id: &AccountId) -> Option<f64> {
+        let state = self.state.lock().unwrap();
+        let account = state
+            .accounts
+            .iter()
+            .find(|account| account.account_id == account_id.as_str())?;
+
+        Some(account.balance)
+    }
+
+    /// Current credit of the account, None if it does not exist.
+    pub fn get_credit(&self, account_id: &AccountId) -> Option<f64> {
+        let state = self.state.lock().unwrap();
+        let account = state
+            .accounts
+            .iter()
+            .find(|account| account.account_id == account_id.as_str())?;
+
+        Some(account.credit)
+    }
+
+    /// Current status of the account (ACTIVE, RESTRICTED, SUSPENDED), None if it does not exist.
+    pub fn get_status(&self, account_id: &AccountId) -> Option<String> {
+        let state = self.state.lock().unwrap();
+        let account = state
+            .accounts
+            .iter()
+            .find(|account| account.account_id == account_id.as_str())?;
+
+        Some(account.status.clone())
+    }
+
+    pub fn get_opened_positions_count(&self, account_id: &AccountId) -> usize {
+        self.state
+            .lock()
+            .unwrap()
+            .opened_positions
+            .iter()
+            .filter(|position| position["accountId"] == account_id.as_str())
+            .count()
+    }
+
+    pub fn get_accounts_count(&self) -> usize {
+        self.state.lock().unwrap().accounts.len()
+    }
+
+    /// Number of requests handled so far, including rejected ones.
+    pub fn get_requests_count(&self) -> usize {
+        self.requests_count.load(Ordering::Relaxed)
+    }
+
+    pub fn get_transport(self: &Arc<Self>) -> Arc<MockBrandApiTransport> {
+        Arc::new(MockBrandApiTransport {
+            api: Arc::clone(self),
+        })
+    }
+
+    /// Starts a local http server on a random port.
+    pub async fn serve(self: &Arc<Self>) -> std::io::Result<MockBrandApiServer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let api = Arc::clone(self);
+
+        let task = tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    // e.g. too many open files, waits for connections to close instead of spinning
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                };
+
+                let api = Arc::clone(&api);
+                tokio::spawn(async move {
+                    _ = serve_connection(api, stream).await;
+                });
+            }
+        });
+
+        Ok(MockBrandApiServer { url, task })
+    }
+
+    /// Handles a single request. `url` may be a full url or a path with query.
+    pub fn handle(
+        &self,
+        method: &Method,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> BrandApiTransportResponse {
+        let response = self.handle_request(method, url, headers, body);
+
+        BrandApiTransportResponse {
+            status_code: response.status_code,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: response.body.into_bytes(),
+        }
+    }
+
+    fn handle_request(
+        &self,
+        method: &Method,
+        url: &str,
+        headers: &[(String, String)],
+        body: &[u8],
+    ) -> MockResponse {
+        self.requests_count.fetch_add(1, Ordering::Relaxed);
+        let path = get_path(url);
+        let Some(endpoint) = BrandApiEndpoint::iter()
+            .find(|endpoint| String::from(endpoint) == path && endpoint.get_http_method() == method)
+        else {
+            return MockResponse::error(404, "NOT_FOUND", &format!("Cannot {} {}", method, path));
+        };
+
+        if get_header(headers, "brand-api-key") != Some(self.api_key.as_str()) {
+            return MockResponse::error(401, "UNAUTHORIZED", "Invalid brand-api-key");
+        }
+
+        let request: Value = if body.is_empty() {
+            Value::Null
+        } else {
+            match serde_json::from_slice(body) {
+                Ok(request) => request,
+                Err(err) => return MockResponse::error(400, "VALIDATION_ERROR", &err.to_string()),
+            }
+        };
+
+        let mut state = self.state.lock().unwrap();
+        let idempotency_key = get_header(headers, "Idempotency-Key").map(|key| (endpoint, key.to_string()));
+
+        if let Some(key) = &idempotency_key {
+            if let Some((prev_body, prev_response)) = state.idempotent_responses.get(key) {
+                if prev_body.as_slice() != body {
+                    return MockResponse::error(
+                        409,
+                        "IDEMPOTENCY_CONFLICT",
+                        "Idempotency-Key was used with another request",
+                    );
+                }
+
+                return prev_response.clone();
+            }
+        }
+
+        let response = match handle_endpoint(&mut state, endpoint, &request) {
+            Ok(response) => response,
+            Err(response) => response,
+        };
+
+        if let Some(key) = idempotency_key {
+            if response.status_code < 300 {
+                state
+                    .idempotent_responses
+                    .insert(key, (body.to_vec(), response.clone()));
+            }
+        }
+
+        response
+    }
+}
+
+/// In-process BrandApiTransport backed by MockBrandApi.
+pub struct MockBrandApiTransport {
+    api: Arc<MockBrandApi>,
+}
+
+#[async_trait::async_trait]
+impl BrandApiTransport for MockBrandApiTransport {
+    async fn send(
+        &self,
+        request: BrandApiTransportRequest,
+    ) -> Result<BrandApiTransportResponse, String> {
+        let body = request.body.unwrap_or_default();
+
+        Ok(self
+            .api
+            .handle(&request.method, &request.url, &request.headers, &body))
+    }
+}
+
+/// Running local http server. Stops when dropped.
+pub struct MockBrandApiServer {
+    url: String,
+    task: JoinHandle<()>,
+}
+
+impl MockBrandApiServer {
+    /// Base url to use as BrandApiConfig api url, e.g. http://127.0.0.1:53412
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl Drop for MockBrandApiServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn handle_endpoint(
+    state: &mut MockBrandApiState,
+    endpoint: BrandApiEndpoint,
+    request: &Value,
+) -> Result<MockResponse, MockResponse> {
+    let response = match endpoint {
+        BrandApiEndpoint::CreateUser => {
+            let email = get_str(request, "email")?;
+
+            if state.users.iter().any(|user| user.email.eq_ignore_ascii_case(email)) {
+                return Err(MockResponse::error(400, "DUPLICATE_EMAIL", "Email already exists"));
+            }
+
+            let user_id = uuid::Uuid::new_v4().to_string();
+            state.users.push(MockUser {
+                user_id: user_id.clone(),
+                email: email.to_string(),
+            });
+
+            MockResponse::ok(json!({ "userId": user_id }))
+        }
+        BrandApiEndpoint::CheckEmail => {
+            let email = get_str(request, "email")?;
+            let user = state
+                .users
+                .iter()
+                .find(|user| user.email.eq_ignore_ascii_case(email))
+                .ok_or_else(|| MockResponse::error(404, "USER_NOT_FOUND", "User not found"))?;
+
+            MockResponse::ok(json!({ "userId": user.user_id }))
+        }
+        BrandApiEndpoint::SetUserPassword => {
+            let user_id = get_str(request, "userId")?;
+
+            if !state.users.iter().any(|user| user.user_id == user_id) {
+                return Err(MockResponse::error(404, "USER_NOT_FOUND", "User not found"));
+            }
+
+            MockResponse::empty()
+        }
+        BrandApiEndpoint::GetAccount => {
+            let account = state.find_account(get_str(request, "accountId")?)?;
+
+            MockResponse::ok(account.to_json())
+        }
+        BrandApiEndpoint::CreateAccount => {
+            let user_id = get_str(request, "userId")?.to_string();
+
+            if !state.users.iter().any(|user| user.user_id == user_id) {
+                return Err(MockResponse::error(404, "USER_NOT_FOUND", "User not found"));
+            }
+
+            let group_id = match request.get("groupId").and_then(Value::as_str) {
+                Some(group_id) => {
+                    if !state.groups.iter().any(|group| group["id"] == group_id) {
+                        return Err(MockResponse::error(400, "INVALID_GROUP", "Group not found"));
+                    }
+
+                    group_id.to_string()
+                }
+                None => state.default_group_id.clone(),
+            };
+
+            let account_type = get_str(request, "type")?.to_string();
+            let prefix = if account_type == "DEMO" { "D" } else { "L" };
+            let account = MockAccount {
+                account_id: format!("{}#{}", prefix, state.get_next_id()),
+                account_name: get_str(request, "accountName")?.to_string(),
+                user_id,
+                group_id,
+                account_type,
+                status: "ACTIVE".to_string(),
+                currency: get_str(request, "currency")?.to_string(),
+                balance: 0.0,
+                credit: 0.0,
+                created_date_time: format_date_time(&Utc::now()),
+            };
+            let response = MockResponse::ok(account.to_json());
+            state.accounts.push(account);
+
+            response
+        }
+        BrandApiEndpoint::ActivateAccount
+        | BrandApiEndpoint::RestrictAccount
+        | BrandApiEndpoint::SuspendAccount => {
+            let status = match endpoint {
+                BrandApiEndpoint::ActivateAccount => "ACTIVE",
+                BrandApiEndpoint::RestrictAccount => "RESTRICTED",
+                _ => "SUSPENDED",
+            };
+            let account = state.find_account(get_str(request, "accountId")?)?;
+            account.status = status.to_string();
+
+            MockResponse::ok(json!({ "accountId": account.account_id, "status": status }))
+        }
+        BrandApiEndpoint::SetAccountGroup => {
+            let group_id = get_str(request, "newGroupId")?.to_string();
+
+            if !state.groups.iter().any(|group| group["id"] == group_id.as_str()) {
+                return Err(MockResponse::error(400, "INVALID_GROUP", "Group not found"));
+            }
+
+            let account = state.find_account(get_str(request, "accountId")?)?;
+            account.group_id = group_id;
+
+            MockResponse::empty()
+        }
+        BrandApiEndpoint::CloseAccountPositions => {
+            let account_id = state
+                .find_account(get_str(request, "accountId")?)?
+                .account_id
+                .clone();
+            let (closed, opened): (Vec<Value>, Vec<Value>) = state
+                .opened_positions
+                .drain(..)
+                .partition(|position| position["accountId"] == account_id.as_str());
+            state.opened_positions = opened;
+            let position_ids: Vec<Value> = closed
+                .into_iter()
+                .map(|position| position["positionId"].clone())
+                .collect();
+
+            MockResponse::ok(json!({ "positionIdsOrderedToBeClosed": position_ids }))
+        }
+        BrandApiEndpoint::CreditAccount => {
+            let amount = get_amount(request)?;
+
+            if amount == 0.0 {
+                return Err(MockResponse::error(400, "INVALID_AMOUNT", "Amount must not be zero"));
+            }
+
+            state.find_account(get_str(request, "accountId")?)?.credit += amount;
+
+            MockResponse::ok(json!({ "operationId": state.get_next_id().to_string() }))
+        }
+        BrandApiEndpoint::Deposit | BrandApiEndpoint::Withdraw => {
+            let amount = get_amount(request)?;
+
+            if amount <= 0.0 {
+                return Err(MockResponse::error(400, "INVALID_AMOUNT", "Amount must be positive"));
+            }
+
+            let account = state.find_account(get_str(request, "accountId")?)?;
+
+            if let BrandApiEndpoint::Deposit = endpoint {
+                account.balance += amount;
+            } else if account.balance < amount {
+                return Err(MockResponse::error(400, "INSUFFICIENT_FUNDS", "Insufficient funds"));
+            } else {
+                account.balance -= amount;
+            }
+
+            MockResponse::ok(json!({ "operationId": state.get_next_id().to_string() }))
+        }
+        BrandApiEndpoint::GetInstruments => MockResponse::ok(json!({ "data": state.instruments })),
+        BrandApiEndpoint::GetAssets => MockResponse::ok(json!({ "data": state.assets })),
+        BrandApiEndpoint::GetGroups => MockResponse::ok(json!({ "data": state.groups })),
+        BrandApiEndpoint::GetOpenedPositions => {
+            let account_ids = get_account_ids(request, "accountId");
+            let data = filter_report(&state.opened_positions, &account_ids, None, None);
+
+            MockResponse::ok(json!({ "data": data }))
+        }
+        BrandApiEndpoint::GetAccountsReport => {
+            let account_ids = get_account_ids(request, "accountIds");
+            let account_type = request.get("type").and_then(Value::as_str);
+            let account_status = request.get("accountStatus").and_then(Value::as_str);
+            let data: Vec<Value> = state
+                .accounts
+                .iter()
+                .filter(|account| {
+                    account_ids
+                        .as_ref()
+                        .is_none_or(|ids| ids.contains(&account.account_id))
+                })
+                .filter(|account| account_type.is_none_or(|t| account.account_type == t))
+                .filter(|account| account_status.is_none_or(|s| account.status == s))
+                .map(MockAccount::to_report_json)
+                .collect();
+
+            MockResponse::ok(json!({ "data": data }))
+        }
+        BrandApiEndpoint::GetClosedTradesHistoryReport => {
+            check_report_range(request)?;
+            let data = filter_report(
+                &state.closed_trades,
+                &get_account_ids(request, "accountIds"),
+                Some("closeMilliseconds"),
+                Some(request),
+            );
+
+            MockResponse::ok(json!({ "data": data }))
+        }
+        BrandApiEndpoint::GetClosedPositionsHistoryReport => {
+            check_report_range(request)?;
+            let data = filter_report(
+                &state.closed_positions,
+                &get_account_ids(request, "accountIds"),
+                Some("closeDateTime"),
+                Some(request),
+            );
+
+            MockResponse::ok(json!({ "data": data }))
+        }
+        BrandApiEndpoint::GetTradesHistoryReport => {
+            let data = filter_report(
+                &state.trades,
+                &get_account_ids(request, "accountId"),
+                Some("tradeDateTime"),
+                Some(request),
+            );
+
+            MockResponse::ok(json!({ "data": data }))
+        }
+        BrandApiEndpoint::GetClosedTradesHistoryReportV1 => {
+            let data = filter_report(
+                &state.closed_trades,
+                &get_account_ids(request, "accountId"),
+                Some("closeMilliseconds"),
+                Some(request),
+            );
+
+            MockResponse::ok(get_cursor_page(data, request))
+        }
+        BrandApiEndpoint::GetTradesHistoryReportV1 => {
+            let data = filter_report(
+                &state.trades,
+                &get_account_ids(request, "accountId"),
+                Some("tradeDateTime"),
+                Some(request),
+            );
+
+            MockResponse::ok(get_cursor_page(data, request))
+        }
+        BrandApiEndpoint::GetOrders => {
+            let orders = filter_report(&state.orders, &get_account_ids(request, "accountId"), None, None);
+            let offset = request.get("offset").and_then(Value::as_u64).unwrap_or(0) as usize;
+            let limit = request.get("limit").and_then(Value::as_u64).unwrap_or(1000) as usize;
+            let limit = limit.min(state.max_orders_page_size);
+            let data: Vec<Value> = orders.into_iter().skip(offset).take(limit).collect();
+
+            MockResponse::ok(json!({ "data": data }))
+        }
+        BrandApiEndpoint::CancelOrder => {
+            let order_id = get_str(request, "orderId")?;
+            let count = state.orders.len();
+            state.orders.retain(|order| order["orderId"] != order_id);
+
+            if state.orders.len() == count {
+                return Err(MockResponse::error(404, "ORDER_NOT_FOUND", "Order not found"));
+            }
+
+            MockResponse::empty()
+        }
+        BrandApiEndpoint::MonthlyActiveAccounts => {
+            if request.get("returnType").and_then(Value::as_str) == Some("csv") {
+                MockResponse {
+                    status_code: 200,
+                    body: to_csv(&state.monthly_active_accounts),
+                }
+            } else {
+                MockResponse::ok(json!({ "data": state.monthly_active_accounts }))
+            }
+        }
+        BrandApiEndpoint::GetApiStatus => MockResponse::ok(json!({ "status": "ok" })),
+        BrandApiEndpoint::IsApiAlive => MockResponse {
+            status_code: 200,
+            body: "1".to_string(),
+        },
+    };
+
+    Ok(response)
+}
+
+/// Filters report rows by account and by the date range of the request.
+fn filter_report(
+    rows: &[Value],
+    account_ids: &Option<Vec<String>>,
+    date_field: Option<&str>,
+    request: Option<&Value>,
+) -> Vec<Value> {
+    let start = request.and_then(|request| get_date_time(request.get("startDateTime")?));
+    let end = request.and_then(|request| get_date_time(request.get("endDateTime")?));
+
+    rows.iter()
+        .filter(|row| {
+            account_ids.as_ref().is_none_or(|ids| {
+                row["accountId"]
+                    .as_str()
+                    .is_some_and(|id| ids.iter().any(|account_id| account_id == id))
+            })
+        })
+        .filter(|row| {
+            let Some(date) = date_field.and_then(|field| get_date_time(row.get(field)?)) else {
+                return true;
+            };
+
+            start.is_none_or(|start| date >= start) && end.is_none_or(|end| date <= end)
+        })
+        .cloned()
+        .collect()
+}
+
+/// Cursor of the mock is the offset of the page. The default cursor requests the first page.
+fn get_cursor_page(rows: Vec<Value>, request: &Value) -> Value {
+    let offset = request
+        .get("cursor")
+        .and_then(Value::as_str)
+        .filter(|cursor| *cursor != get_default_cursor())
+        .and_then(|cursor| cursor.parse().ok())
+        .unwrap_or(0usize);
+    let limit = request.get("limit").and_then(Value::as_u64).unwrap_or(1000) as usize;
+    let next_offset = offset + limit;
+    let data: Vec<Value> = rows.iter().skip(offset).take(limit).cloned().collect();
+    let links = (next_offset < rows.len()).then(|| {
+        json!({
+            "next": {
+                "url": null,
+                "params": {
+                    "accountId": request.get("accountId").cloned().unwrap_or_else(|| json!("")),
+                    "type": request.get("type").cloned().unwrap_or_default(),
+                    "cursor": next_offset.to_string(),
+                    "limit": limit,
+                }
+            }
+        })
+    });
+
+    json!({ "data": data, "links": links })
+}
+
+/// Closed trades and closed positions reports accept at most 31 days.
+fn check_report_range(request: &Value) -> Result<(), MockResponse> {
+    let start = request.get("startDateTime").and_then(get_date_time);
+    let end = request.get("endDateTime").and_then(get_date_time);
+
+    match (start, end) {
+        (Some(start), Some(end)) if start <= end && end - start <= MAX_REPORT_WINDOW => Ok(()),
+        _ => Err(MockResponse::error(
+            400,
+            "VALIDATION_ERROR",
+            "startDateTime must be before endDateTime and the range can be a maximum of 31 days",
+        )),
+    }
+}
+
+fn get_account_ids(request: &Value, field: &str) -> Option<Vec<String>> {
+    match request.get(field)? {
+        Value::String(account_id) => Some(vec![account_id.clone()]),
+        Value::Array(account_ids) => Some(
+            account_ids
+                .iter()
+                .filter_map(|id| id.as_str().map(str::to_string))
+                .collect(),
+        ),
+        _ => None,
+    }
+}
+
+fn get_str<'a>(request: &'a Value, field: &str) -> Result<&'a str, MockResponse> {
+    request.get(field).and_then(Value::as_str).ok_or_else(|| {
+        MockResponse::error(400, "VALIDATION_ERROR", &format!("{} is required", field))
+    })
+}
+
+fn get_amount(request: &Value) -> Result<f64, MockResponse> {
+    let amount = match request.get("amount") {
+        Some(Value::String(amount)) => amount.parse().ok(),
+        Some(Value::Number(amount)) => amount.as_f64(),
+        _ => None,
+    };
+
+    amount.ok_or_else(|| MockResponse::error(400, "INVALID_AMOUNT", "amount must be a number"))
+}
+
+/// Accepts ISO strings and epoch milliseconds, as strings or numbers.
+fn get_date_time(value: &Value) -> Option<DateTime<Utc>> {
+    match value {
+        Value::String(value) => parse_date_time(value),
+        Value::Number(value) => DateTime::from_timestamp_millis(value.as_i64()?),
+        _ => None,
+    }
+}
+
+fn get_path(url: &str) -> &str {
+    let path = match url.find("/brand-api") {
+        Some(index) => &url[index..],
+        None => url,
+    };
+
+    path.split('?').next().unwrap_or_default()
+}
+
+fn get_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+fn format_amount(amount: f64) -> String {
+    format!("{:.2}", amount)
+}
+
+fn to_csv(rows: &[Value]) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+
+    for row in rows {
+        let row: MonthlyActiveAccountModel =
+            serde_json::from_value(row.clone()).expect("fixture must be deserializable");
+        writer.serialize(row).expect("fixture must be serializable");
+    }
+
+    String::from_utf8(writer.into_inner().expect("csv must be written")).expect("csv must be utf8")
+}
+
+fn to_json<T: Serialize>(model: &T) -> Value {
+    serde_json::to_value(model).expect("fixture must be serializable")
+}
+
+/// Minimal HTTP/1.1: one request per connection, body sized by Content-Length.
+async fn serve_connection(api: Arc<MockBrandApi>, mut stream: TcpStream) -> std::io::Result<()> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let header_end = loop {
+        let read = stream.read(&mut chunk).await?;
+
+        if read == 0 {
+            return Ok(());
+        }
+
+        buffer.extend_from_slice(&chunk[..read]);
+
+        if let Some(index) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break index;
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default();
+    let url = request_line.next().unwrap_or_default().to_string();
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_string(), value.trim().to_string()))
+        })
+        .collect();
+    let content_length: usize = get_header(&headers, "Content-Length")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+
+    let mut body = buffer[header_end + 4..].to_vec();
+
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).await?;
+
+        if read == 0 {
+            break;
+        }
+
+        body.extend_from_slice(&chunk[..read]);
+    }
+
+    let method = Method::from_bytes(method.as_bytes()).unwrap_or(Method::GET);
+    let response = api.handle(&method, &url, &headers, &body);
+    let reason = http::StatusCode::from_u16(response.status_code)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default();
+    let head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status_code,
+        reason,
+        response.body.len()
+    );
+
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand::errors::{BrandApiError, WebservicesErrorCode};
+    use crate::brand::test_support::{create_account, get_client};
+    use crate::brand::{
+        AccountOperationRequest, AccountStatus, CheckEmailRequest, GetAccountRequest,
+        GetOrdersRequest, UpdateAccountStatusRequest,
+    };
+    use crate::models::AccountType;
+
+    fn get_operation(account_id: &AccountId, amount: &str) -> AccountOperationRequest {
+        AccountOperationRequest {
+            account_id: account_id.clone(),
+            amount: amount.parse().unwrap(),
+            note: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn tracks_users_accounts_and_balances() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let account_id = create_account(&client).await;
+
+        client
+            .deposit_account(&get_operation(&account_id, "100"), None)
+            .await
+            .unwrap();
+        client
+            .withdraw_account(&get_operation(&account_id, "30"), None)
+            .await
+            .unwrap();
+
+        let account = client
+            .get_account(&GetAccountRequest {
+                account_id: account_id.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(account.balance.to_string(), "70.00");
+        assert_eq!(api.get_balance(&account_id), Some(70.0));
+
+        let user = client
+            .check_email(&CheckEmailRequest {
+                email: "trader@example.com".to_string(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(user.user_id, account.user_id);
+    }
+
+    #[tokio::test]
+    async fn returns_business_errors() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let account_id = create_account(&client).await;
+
+        let err = client
+            .withdraw_account(&get_operation(&account_id, "1"), None)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.as_api_error().unwrap().error_code(),
+            Some(&WebservicesErrorCode::InsufficientFunds)
+        );
+
+        let err = client
+            .get_account(&GetAccountRequest {
+                account_id: "L#0".into(),
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err.as_api_error(),
+            Some(BrandApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn replays_idempotent_requests() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let account_id = create_account(&client).await;
+        let request = get_operation(&account_id, "100");
+
+        let first = client
+            .deposit_account(&request, Some("key-1"))
+            .await
+            .unwrap();
+        let second = client
+            .deposit_account(&request, Some("key-1"))
+            .await
+            .unwrap();
+
+        assert_eq!(first.operation_id, second.operation_id);
+        assert_eq!(api.get_balance(&account_id), Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn changes_account_status() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = get_client(&api);
+        let account_id = create_account(&client).await;
+
+        let resp = client
+            .suspend_account(&UpdateAccountStatusRequest {
+                account_id: account_id.clone(),
+            })
+            .await
+            .unwrap();
+
+        assert!(matches!(resp.status, AccountStatus::Suspended));
+        assert_eq!(api.get_status(&account_id).as_deref(), Some("SUSPENDED"));
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_api_key() {
+        let api = Arc::new(MockBrandApi::new("another-key"));
+        let client = get_client(&api);
+
+        let err = client
+            .get_orders(&GetOrdersRequest {
+                account_type: AccountType::Live,
+                account_id: None,
+                offset: None,
+                limit: None,
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err.as_api_error(),
+            Some(BrandApiError::Unauthorized(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn serves_http() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let server = api.serve().await.unwrap();
+        let mut stream = TcpStream::connect(server.get_url().trim_start_matches("http://"))
+            .await
+            .unwrap();
+        let body = r#"{"type":"LIVE"}"#;
+        let request = format!(
+            "POST /brand-api/v1/groups/all HTTP/1.1\r\nbrand-api-key: key\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.ends_with(r#"{"data":[{"id":"1","name":"default"}]}"#));
+    }
+}
@@ -1,5 +1,21 @@
 pub mod api_client;
+pub mod bulk;
+pub mod builders;
+pub mod date_time;
 pub mod endpoints;
 pub mod errors;
+pub mod idempotency;
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock_server;
 pub mod models;
+pub mod pagination;
+pub mod phase_transition;
+pub mod provisioning;
+pub mod rate_limiter;
+pub mod report_range;
+pub mod retry;
+#[cfg(test)]
+mod test_support;
+pub mod transport;
+pub mod validation;
 pub use models::*;
@@ -1,4 +1,6 @@
-use crate::models::AccountType;
+use crate::models::{
+    AccountId, AccountType, Amount, GroupId, OperationId, OrderId, PositionId, TradeId, UserId,
+};
 use chrono::{DateTime, Utc};
 use serde::Serialize;
 use serde_derive::Deserialize;
@@ -16,7 +18,7 @@ pub struct CreateUserRequest {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateUserResponse {
     #[serde(rename = "userId")]
-    pub user_id: String,
+    pub user_id: UserId,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -27,20 +29,20 @@ pub struct CheckEmailRequest {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CheckEmailResponse {
     #[serde(rename = "userId")]
-    pub user_id: String,
+    pub user_id: UserId,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SetUserPasswordRequest {
     #[serde(rename = "userId")]
-    pub user_id: String,
+    pub user_id: UserId,
     pub password: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct GetAccountRequest {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
 }
 
 /// Represents the reason for trading being disabled.
@@ -60,15 +62,15 @@ pub struct AccountModel {
 
     /// The unique identifier for the account.
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
 
     /// The unique identifier for the user associated with the account (UUID).
     #[serde(rename = "userId")]
-    pub user_id: String,
+    pub user_id: UserId,
 
     /// The user group ID of the account.
     #[serde(rename = "userGroupId")]
-    pub user_group_id: String,
+    pub user_group_id: GroupId,
 
     /// The type of the account to create (e.g., LIVE, DEMO).
     #[serde(rename = "type")]
@@ -94,38 +96,39 @@ pub struct AccountModel {
 
     /// The current account balance.
     #[serde(rename = "balance")]
-    pub balance: String,
+    pub balance: Amount,
 
     /// The current account credit.
     #[serde(rename = "credit")]
-    pub credit: String,
+    pub credit: Amount,
 
     /// The current account equity.
     #[serde(rename = "equity")]
-    pub equity: String,
+    pub equity: Amount,
 
     /// The current account profit and loss (PNL).
     #[serde(rename = "pnl")]
-    pub pnl: String,
+    pub pnl: Amount,
 
     /// The current account margin available.
     #[serde(rename = "marginAvailable")]
-    pub margin_available: String,
+    pub margin_available: Amount,
 
     /// The current account margin used.
     #[serde(rename = "marginUsed")]
-    pub margin_used: String,
+    pub margin_used: Amount,
 
     /// The date and time when the account was created.
     #[serde(rename = "createdDateTime")]
-    pub created_date_time: String,
+    #[serde(with = "crate::brand::date_time")]
+    pub created_date_time: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreateAccountRequest {
     /// The unique identifier for the user (UUID).
     #[serde(rename = "userId")]
-    pub user_id: String,
+    pub user_id: UserId,
 
     /// The name of the account.
     #[serde(rename = "accountName")]
@@ -141,13 +144,13 @@ pub struct CreateAccountRequest {
 
     /// The ID of the group to place the account into. If not provided, placed into the brand's default group.
     #[serde(rename = "groupId")]
-    pub group_id: Option<String>,
+    pub group_id: Option<GroupId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdateAccountStatusRequest {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
 }
 
 #[derive(strum::Display, Debug, Clone, Serialize, Deserialize)]
@@ -166,43 +169,43 @@ pub enum AccountStatus {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UpdateAccountStatusResponse {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     pub status: AccountStatus,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SetAccountGroupRequest {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     #[serde(rename = "newGroupId")]
-    pub group_id: String,
+    pub group_id: GroupId,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CloseAccountPositionsRequest {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CloseAccountPositionsResponse {
     #[serde(rename = "positionIdsOrderedToBeClosed")]
-    pub position_ids: Vec<String>,
+    pub position_ids: Vec<PositionId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreditAccountRequest {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     /// Amount of the operation. Positive to add, negative to subtract.
-    pub amount: String,
+    pub amount: Amount,
     pub note: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct CreditAccountResponse {
     #[serde(rename = "operationId")]
-    pub operation_id: String,
+    pub operation_id: OperationId,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -229,16 +232,16 @@ pub struct GetOpenedPositionsRequest {
     pub account_type: AccountType,
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "accountId")]
-    pub account_id: Option<String>,
+    pub account_id: Option<AccountId>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OpenedPositionModel {
     #[serde(rename = "positionId")]
-    pub id: String,
+    pub id: PositionId,
 
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
 
     #[serde(rename = "lots")]
     pub lots: String,
@@ -253,10 +256,10 @@ pub struct OpenedPositionModel {
     pub open_date_time: DateTime<Utc>,
 
     #[serde(rename = "pnl")]
-    pub pnl: String,
+    pub pnl: Amount,
 
     #[serde(rename = "swap")]
-    pub swap: String,
+    pub swap: Amount,
 
     #[serde(rename = "slPrice")]
     pub sl_price: Option<String>,
@@ -277,7 +280,7 @@ pub struct OpenedPositionModel {
     pub current_price: String,
 
     #[serde(rename = "commission")]
-    pub commission: String,
+    pub commission: Amount,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -290,17 +293,17 @@ pub struct GetOpenedPositionsResponse {
 pub struct GetClosedTradesReportRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "accountIds")]
-    pub account_ids: Option<Vec<String>>,
+    pub account_ids: Option<Vec<AccountId>>,
     #[serde(rename = "type")]
     pub account_type: AccountType,
-    /// Start time in ISO format. 2021-12-31T23:59:59.999Z.
-    /// Must be before end date.
+    /// Must be before end date. Sent in ISO format, e.g. 2021-12-31T23:59:59.999Z.
     #[serde(rename = "startDateTime")]
-    pub start_date_time: String,
-    /// End time in ISO format. 2021-12-31T23:59:59.999Z
-    /// Must be after start date.
+    #[serde(with = "crate::brand::date_time")]
+    pub start_date_time: DateTime<Utc>,
+    /// Must be after start date. Sent in ISO format, e.g. 2021-12-31T23:59:59.999Z.
     #[serde(rename = "endDateTime")]
-    pub end_date_time: String,
+    #[serde(with = "crate::brand::date_time")]
+    pub end_date_time: DateTime<Utc>,
 }
 
 pub fn get_default_cursor() -> String {
@@ -313,10 +316,9 @@ pub struct ClosedTradeReportModel {
     pub instrument: String,
 
     #[serde(rename = "openMilliseconds")]
-    pub open_milliseconds: String,
+    #[serde(with = "crate::brand::date_time::millis")]
+    pub open_milliseconds: DateTime<Utc>,
 
-    //#[serde(rename = "openDateTime")]
-    //pub open_date_time: DateTime<Utc>,
     #[serde(rename = "orderType")]
     pub order_type: String,
 
@@ -333,26 +335,25 @@ pub struct ClosedTradeReportModel {
     pub close_price: String,
 
     #[serde(rename = "closeMilliseconds")]
-    pub close_milliseconds: String,
+    #[serde(with = "crate::brand::date_time::millis")]
+    pub close_milliseconds: DateTime<Utc>,
 
-    //#[serde(rename = "closeDateTime")]
-    //pub close_date_time: DateTime<Utc>,
     #[serde(rename = "openAmount")]
     pub open_amount: String,
 
     #[serde(rename = "closeTradeId")]
-    pub close_trade_id: String,
+    pub close_trade_id: TradeId,
 
     #[serde(rename = "openTradeId")]
-    pub open_trade_id: String,
+    pub open_trade_id: TradeId,
 
     #[serde(rename = "closeOrderId")]
-    pub close_order_id: String,
+    pub close_order_id: OrderId,
 
     #[serde(rename = "positionId")]
-    pub position_id: String,
+    pub position_id: PositionId,
     #[serde(rename = "openOrderId")]
-    pub open_order_id: String,
+    pub open_order_id: OrderId,
     #[serde(rename = "strategyId")]
     pub strategy_id: Option<String>,
     #[serde(rename = "slPrice")]
@@ -365,19 +366,19 @@ pub struct ClosedTradeReportModel {
     #[serde(rename = "tpPrice")]
     pub tp_price: Option<String>,
     #[serde(rename = "commission")]
-    pub commission: String,
+    pub commission: Amount,
     #[serde(rename = "swap")]
-    pub swap: String,
+    pub swap: Amount,
     #[serde(rename = "profit")]
-    pub profit: String,
+    pub profit: Amount,
     #[serde(rename = "netProfit")]
-    pub net_profit: String,
+    pub net_profit: Amount,
     #[serde(rename = "lotSize")]
     pub lot_size: String,
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     #[serde(rename = "userGroupId")]
-    pub user_group_id: String,
+    pub user_group_id: GroupId,
 }
 
 /// Represents pagination links with associated parameters.
@@ -402,13 +403,61 @@ pub struct NextPageLink {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct NextPageLinkParams {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    #[serde(default)]
+    pub account_id: AccountId,
     #[serde(rename = "type")]
     pub account_type: AccountType,
     pub cursor: String,
+    #[serde(default)]
     pub limit: u32,
 }
 
+impl PageLinks {
+    /// Cursor of the next page. None if this is the last page.
+    pub fn get_next_cursor(&self) -> Option<&str> {
+        self.next
+            .as_ref()
+            .map(|next| next.params.cursor.as_str())
+            .filter(|cursor| !cursor.is_empty())
+    }
+}
+
+/// Request of the v1 reports paginated with a cursor.
+/// Use get_default_cursor() to request the first page.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GetCursorReportRequest {
+    #[serde(rename = "type")]
+    pub account_type: AccountType,
+    #[serde(rename = "accountId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_id: Option<AccountId>,
+    #[serde(rename = "startDateTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, with = "crate::brand::date_time::option")]
+    pub start_date_time: Option<DateTime<Utc>>,
+    #[serde(rename = "endDateTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, with = "crate::brand::date_time::option")]
+    pub end_date_time: Option<DateTime<Utc>>,
+    #[serde(default = "get_default_cursor")]
+    pub cursor: String,
+    /// Page size.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetClosedTradesReportV1Response {
+    pub data: Vec<ClosedTradeReportModel>,
+    pub links: Option<PageLinks>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GetTradesReportV1Response {
+    pub data: Vec<TradeReportModel>,
+    pub links: Option<PageLinks>,
+}
+
 #[derive(strum::Display, Debug, Clone, Serialize, Deserialize)]
 pub enum SlOrderType {
     #[strum(to_string = "STOP")]
@@ -507,7 +556,7 @@ pub enum OpenedPositionSide {
 pub struct GetClosedTradesReportResponse {
     pub data: Vec<ClosedTradeReportModel>,
     // Links to the next page of the report. Use params for the next page URL search params.
-    // pub links: PageLinks, seems to be removed on v2
+    // pub links: PageLinks, seems to be removed on v2, see GetClosedTradesReportV1Response
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -519,7 +568,7 @@ pub struct GetGroupsRequest {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct GroupModel {
     pub name: String,
-    pub id: String,
+    pub id: GroupId,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -533,7 +582,7 @@ pub struct GetAccountsReportRequest {
     pub account_type: AccountType,
     #[serde(rename = "accountIds")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub account_ids: Option<Vec<String>>,
+    pub account_ids: Option<Vec<AccountId>>,
     #[serde(rename = "accountStatus")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub account_status: Option<AccountStatus>,
@@ -547,17 +596,17 @@ pub struct GetAccountsReportResponse {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AccountReportModel {
     #[serde(rename = "accountId")]
-    pub account_id: String,
-    pub balance: String,
-    pub credit: String,
-    pub equity: String,
-    pub pnl: String,
+    pub account_id: AccountId,
+    pub balance: Amount,
+    pub credit: Amount,
+    pub equity: Amount,
+    pub pnl: Amount,
     #[serde(rename = "marginUsed")]
-    pub margin_used: String,
+    pub margin_used: Amount,
     #[serde(rename = "marginAvailable")]
-    pub margin_available: String,
+    pub margin_available: Amount,
     #[serde(rename = "userGroupId")]
-    pub user_group_id: String,
+    pub user_group_id: GroupId,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -571,15 +620,17 @@ pub struct GetTradesReportRequest {
     pub account_type: AccountType,
     #[serde(rename = "accountId")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub account_ids: Option<Vec<String>>,
+    pub account_ids: Option<Vec<AccountId>>,
     /// Start time in ISO format. 2021-12-31T23:59:59.999Z
     #[serde(rename = "startDateTime")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub start_date_time: Option<String>,
+    #[serde(default, with = "crate::brand::date_time::option")]
+    pub start_date_time: Option<DateTime<Utc>>,
     /// End time in ISO format. 2021-12-31T23:59:59.999Z
     #[serde(rename = "endDateTime")]
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub end_date_time: Option<String>,
+    #[serde(default, with = "crate::brand::date_time::option")]
+    pub end_date_time: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -590,13 +641,13 @@ pub struct GetTradesReportResponse {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TradeReportModel {
     #[serde(rename = "tradeId")]
-    pub trade_id: String,
+    pub trade_id: TradeId,
 
     #[serde(rename = "orderId")]
-    pub order_id: String,
+    pub order_id: OrderId,
 
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
 
     #[serde(rename = "side")]
     pub side: TradeReportSide,
@@ -612,10 +663,11 @@ pub struct TradeReportModel {
     pub trade_time: i64, // trade time in milliseconds since Unix epoch
 
     #[serde(rename = "tradeDateTime")]
-    pub trade_date_time: String, // ISO format
+    #[serde(with = "crate::brand::date_time")]
+    pub trade_date_time: DateTime<Utc>,
 
     #[serde(rename = "price")]
-    pub price: String,
+    pub price: Amount,
 
     #[serde(rename = "lots")]
     pub lots: String,
@@ -624,13 +676,13 @@ pub struct TradeReportModel {
     pub instrument: String,
 
     #[serde(rename = "positionId")]
-    pub position_id: String,
+    pub position_id: PositionId,
 
     #[serde(rename = "pnl")]
-    pub pnl: String,
+    pub pnl: Amount,
 
     #[serde(rename = "executionFee")]
-    pub execution_fee: String,
+    pub execution_fee: Amount,
 
     #[serde(rename = "stopLoss")]
     pub stop_loss: Option<String>,
@@ -641,9 +693,9 @@ pub struct TradeReportModel {
     #[serde(rename = "takeProfit")]
     pub take_profit: Option<String>,
     #[serde(rename = "swap")]
-    pub swap: Option<String>,
+    pub swap: Option<Amount>,
     #[serde(rename = "netPnl")]
-    pub net_pnl: String,
+    pub net_pnl: Amount,
 }
 
 // Enums for trade sides, order types, and position status
@@ -716,7 +768,7 @@ pub struct CancelOrderRequest {
     #[serde(rename = "type")]
     pub account_type: AccountType,
     #[serde(rename = "orderId")]
-    pub order_id: String,
+    pub order_id: OrderId,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -724,7 +776,7 @@ pub struct GetOrdersRequest {
     #[serde(rename = "type")]
     pub account_type: AccountType,
     #[serde(rename = "accountId")]
-    pub account_id: Option<String>,
+    pub account_id: Option<AccountId>,
     /// Default is 0
     #[serde(skip_serializing_if = "Option::is_none")]
     pub offset: Option<i32>,
@@ -741,7 +793,7 @@ pub struct GetOrdersResponse {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OrderModel {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     #[serde(rename = "amount")]
     pub amount: String,
     #[serde(rename = "lotSize")]
@@ -749,17 +801,19 @@ pub struct OrderModel {
     #[serde(rename = "averageFilledPrice")]
     pub average_filled_price: Option<String>,
     #[serde(rename = "createdDateTime")]
-    pub created_date_time: String,
+    #[serde(with = "crate::brand::date_time")]
+    pub created_date_time: DateTime<Utc>,
     #[serde(rename = "expireDateTime")]
-    pub expire_date_time: Option<String>,
+    #[serde(default, with = "crate::brand::date_time::option")]
+    pub expire_date_time: Option<DateTime<Utc>>,
     #[serde(rename = "expireTime")]
     pub expire_time: Option<String>,
     #[serde(rename = "filledAmount")]
     pub filled_amount: String,
     #[serde(rename = "orderId")]
-    pub order_id: String,
+    pub order_id: OrderId,
     #[serde(rename = "positionId")]
-    pub position_id: Option<String>,
+    pub position_id: Option<PositionId>,
     #[serde(rename = "price")]
     pub price: String,
     #[serde(rename = "side")]
@@ -900,16 +954,16 @@ pub enum OrderType {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AccountOperationRequest {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     /// Amount of the operation. Must be positive.
-    pub amount: String,
+    pub amount: Amount,
     pub note: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AccountOperationResponse {
     #[serde(rename = "operationId")]
-    pub operation_id: String,
+    pub operation_id: OperationId,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -919,7 +973,17 @@ pub struct MonthlyActiveAccountsRequest {
     pub for_month: String,
     /// Return data as Json or binary CSV.
     #[serde(rename = "returnType")]
-    pub return_type: String,
+    pub return_type: ReturnType,
+}
+
+#[derive(strum::Display, Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ReturnType {
+    #[strum(to_string = "json")]
+    #[serde(rename = "json")]
+    Json,
+    #[strum(to_string = "csv")]
+    #[serde(rename = "csv")]
+    Csv,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -930,7 +994,7 @@ pub struct MonthlyActiveAccountsResponse {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MonthlyActiveAccountModel {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     pub group: String,
     pub sessions: i32,
     pub events: i32,
@@ -945,17 +1009,17 @@ pub struct MonthlyActiveAccountModel {
 pub struct GetClosedPositionsReportRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "accountIds")]
-    pub account_ids: Option<Vec<String>>,
+    pub account_ids: Option<Vec<AccountId>>,
     #[serde(rename = "type")]
     pub account_type: AccountType,
-    /// Start time in ISO format. 2021-12-31T23:59:59.999Z.
-    /// Must be before end date.
+    /// Must be before end date. Sent in ISO format, e.g. 2021-12-31T23:59:59.999Z.
     #[serde(rename = "startDateTime")]
-    pub start_date_time: String,
-    /// End time in ISO format. 2021-12-31T23:59:59.999Z
-    /// Must be after start date.
+    #[serde(with = "crate::brand::date_time")]
+    pub start_date_time: DateTime<Utc>,
+    /// Must be after start date. Sent in ISO format, e.g. 2021-12-31T23:59:59.999Z.
     #[serde(rename = "endDateTime")]
-    pub end_date_time: String,
+    #[serde(with = "crate::brand::date_time")]
+    pub end_date_time: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -968,20 +1032,22 @@ pub struct GetClosedPositionsReportResponse {
 pub struct ClosedPositionModel {
     pub instrument: String,
     pub lot_size: String,
-    pub account_id: String,
-    pub close_trade_id: String,
-    pub position_id: String,
-    pub close_order_id: String,
-    pub open_order_id: String,
+    pub account_id: AccountId,
+    pub close_trade_id: TradeId,
+    pub position_id: PositionId,
+    pub close_order_id: OrderId,
+    pub open_order_id: OrderId,
     pub duration_sec: String,
 
-    pub open_date_time: String,
-    pub close_date_time: String,
+    #[serde(with = "crate::brand::date_time")]
+    pub open_date_time: DateTime<Utc>,
+    #[serde(with = "crate::brand::date_time")]
+    pub close_date_time: DateTime<Utc>,
 
-    pub profit: String,
-    pub net_profit: String,
-    pub commission: String,
-    pub swap: String,
+    pub profit: Amount,
+    pub net_profit: Amount,
+    pub commission: Amount,
+    pub swap: Amount,
     pub amount: String,
 
     pub open_price: String,
@@ -995,5 +1061,5 @@ pub struct ClosedPositionModel {
     pub open_trade_cross_price: String,
     pub close_trade_cross_price: String,
 
-    pub user_group_id: String,
+    pub user_group_id: GroupId,
 }
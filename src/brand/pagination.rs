@@ -0,0 +1,206 @@
+//! Options and page traits of the paginated streams of BrandApiClient.
+
+use crate::brand::errors::Error;
+use crate::brand::{
+    ClosedTradeReportModel, GetClosedTradesReportV1Response, GetCursorReportRequest,
+    GetTradesReportV1Response, PageLinks, TradeReportModel,
+};
+use futures_util::future::LocalBoxFuture;
+use futures_util::Stream;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// Page size used by the Brand API when no limit is sent.
+pub const DEFAULT_PAGE_SIZE: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrandApiPagination {
+    /// Number of items requested per page. The server may return less.
+    pub page_size: usize,
+    /// Number of pages fetched ahead of the one being consumed. Pages of offset pagination are
+    /// still requested one after another, as each offset depends on the previous page.
+    /// 0 requests the next page only after the current one is consumed.
+    pub prefetch: usize,
+}
+
+impl Default for BrandApiPagination {
+    fn default() -> Self {
+        Self {
+            page_size: DEFAULT_PAGE_SIZE,
+            prefetch: 0,
+        }
+    }
+}
+
+impl BrandApiPagination {
+    pub fn new(page_size: usize) -> Self {
+        Self {
+            page_size: page_size.max(1),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_prefetch(mut self, prefetch: usize) -> Self {
+        self.prefetch = prefetch;
+        self
+    }
+}
+
+type PageFuture<'a, T> = LocalBoxFuture<'a, Result<Vec<T>, Error>>;
+
+/// Items of an endpoint paginated with an offset. The next offset is the previous one plus the
+/// number of returned items, and the stream ends on an empty page or after the first error.
+///
+/// While a page is consumed, up to `prefetch` following pages are requested one after another.
+/// The requests progress whenever the stream is polled.
+pub(crate) struct OffsetPaginatedStream<'a, T, F> {
+    fetch_page: F,
+    limit: i32,
+    prefetch: usize,
+    /// None after the last page or an error.
+    next_offset: Option<i32>,
+    page_in_flight: Option<(i32, PageFuture<'a, T>)>,
+    pages: VecDeque<Result<Vec<T>, Error>>,
+    items: std::vec::IntoIter<T>,
+}
+
+impl<'a, T, F> OffsetPaginatedStream<'a, T, F>
+where
+    F: FnMut(i32, i32) -> PageFuture<'a, T>,
+{
+    /// `fetch_page` requests `limit` items from an offset.
+    pub fn new(offset: i32, pagination: BrandApiPagination, fetch_page: F) -> Self {
+        Self {
+            fetch_page,
+            limit: i32::try_from(pagination.page_size.max(1)).unwrap_or(i32::MAX),
+            prefetch: pagination.prefetch,
+            next_offset: Some(offset),
+            page_in_flight: None,
+            pages: VecDeque::new(),
+            items: Vec::new().into_iter(),
+        }
+    }
+
+    fn on_page(&mut self, offset: i32, page: Result<Vec<T>, Error>) {
+        self.next_offset = None;
+
+        let items = match page {
+            Ok(items) if items.is_empty() => return,
+            Ok(items) => items,
+            Err(err) => {
+                self.pages.push_back(Err(err));
+                return;
+            }
+        };
+        let next_offset = i32::try_from(items.len())
+            .ok()
+            .and_then(|count| offset.checked_add(count));
+        self.pages.push_back(Ok(items));
+
+        match next_offset {
+            Some(next_offset) => self.next_offset = Some(next_offset),
+            None => {
+                let err = format!("Offset overflows i32 after {}", offset);
+                self.pages.push_back(Err(err.into()));
+            }
+        }
+    }
+
+    /// Whether another page should be requested: the current one is consumed or fewer than
+    /// `prefetch` pages are waiting.
+    fn needs_page(&self) -> bool {
+        self.page_in_flight.is_none()
+            && self.next_offset.is_some()
+            && (self.items.as_slice().is_empty() || self.pages.len() < self.prefetch)
+    }
+}
+
+impl<'a, T, F> Stream for OffsetPaginatedStream<'a, T, F>
+where
+    T: Unpin,
+    F: FnMut(i32, i32) -> PageFuture<'a, T> + Unpin,
+{
+    type Item = Result<T, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some((offset, page)) = this.page_in_flight.as_mut() {
+                if let Poll::Ready(page) = page.as_mut().poll(cx) {
+                    let offset = *offset;
+                    this.page_in_flight = None;
+                    this.on_page(offset, page);
+                }
+            }
+
+            if this.items.as_slice().is_empty() {
+                match this.pages.pop_front() {
+                    Some(Ok(items)) => this.items = items.into_iter(),
+                    Some(Err(err)) => return Poll::Ready(Some(Err(err))),
+                    None => {}
+                }
+            }
+
+            if !this.needs_page() {
+                break;
+            }
+
+            if let Some(offset) = this.next_offset {
+                let page = (this.fetch_page)(offset, this.limit);
+                this.page_in_flight = Some((offset, page));
+            }
+        }
+
+        if let Some(item) = this.items.next() {
+            return Poll::Ready(Some(Ok(item)));
+        }
+
+        if this.page_in_flight.is_some() {
+            Poll::Pending
+        } else {
+            Poll::Ready(None)
+        }
+    }
+}
+
+/// Request of an endpoint paginated with a cursor.
+pub trait CursorPaginatedRequest: Clone {
+    fn get_cursor(&self) -> &str;
+    fn set_cursor(&mut self, cursor: String);
+}
+
+/// Page returned by an endpoint paginated with a cursor.
+pub trait CursorPaginatedResponse {
+    type Item;
+
+    /// Items of the page and the links to the next one.
+    fn into_page(self) -> (Vec<Self::Item>, Option<PageLinks>);
+}
+
+impl CursorPaginatedRequest for GetCursorReportRequest {
+    fn get_cursor(&self) -> &str {
+        &self.cursor
+    }
+
+    fn set_cursor(&mut self, cursor: String) {
+        self.cursor = cursor;
+    }
+}
+
+impl CursorPaginatedResponse for GetClosedTradesReportV1Response {
+    type Item = ClosedTradeReportModel;
+
+    fn into_page(self) -> (Vec<Self::Item>, Option<PageLinks>) {
+        (self.data, self.links)
+    }
+}
+
+impl CursorPaginatedResponse for GetTradesReportV1Response {
+    type Item = TradeReportModel;
+
+    fn into_page(self) -> (Vec<Self::Item>, Option<PageLinks>) {
+        (self.data, self.links)
+    }
+}
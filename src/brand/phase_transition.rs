@@ -0,0 +1,672 @@
+//! Moving a trader from an evaluation account to a funded one: a new LIVE account is opened
+//! and funded, then the old account is frozen, its positions are closed and it is archived.
+//! If a step fails before the positions are closed, the completed ones are compensated so the
+//! caller sees all or nothing. Closed positions cannot be reopened, so after that point a failed
+//! transition is finished by running it again.
+
+use crate::brand::api_client::{BrandApiClient, BrandApiConfig};
+use crate::brand::errors::Error;
+use crate::brand::{
+    AccountModel, AccountOperationRequest, AccountStatus, CloseAccountPositionsRequest,
+    CreateAccountRequest, GetAccountRequest, GetOpenedPositionsRequest, SetAccountGroupRequest,
+    UpdateAccountStatusRequest,
+};
+use crate::models::{AccountId, AccountType, Amount, GroupId, OperationId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Maximum time to wait for the positions of the old account to be closed.
+pub const DEFAULT_POSITIONS_CLOSE_TIMEOUT: Duration = Duration::from_secs(30);
+/// Interval between get_opened_positions calls while waiting.
+pub const DEFAULT_POSITIONS_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Status the old account is left in.
+#[derive(strum::Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveMode {
+    /// The account is hidden from the TradeLocker application.
+    Suspend,
+    /// The account stays visible but cannot open positions.
+    Restrict,
+}
+
+/// Steps of a transition in the order they run.
+#[derive(strum::Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhaseTransitionStep {
+    /// Reads the state of the previous runs with the same transition id.
+    LoadState,
+    /// Reads the old account.
+    LoadAccount,
+    CreateAccount,
+    SetAccountGroup,
+    Deposit,
+    /// Restricts the old account so that no positions are opened while closing.
+    FreezeAccount,
+    /// Cannot be compensated: closed positions cannot be reopened.
+    ClosePositions,
+    WaitPositionsClosed,
+    ArchiveAccount,
+}
+
+/// Action undoing a completed step.
+#[derive(Debug, Clone)]
+pub enum PhaseTransitionCompensation {
+    /// Sets the status the old account had before the transition.
+    RestoreAccountStatus {
+        account_id: AccountId,
+        status: AccountStatus,
+    },
+    /// Suspends the new account. Accounts cannot be deleted.
+    SuspendAccount { account_id: AccountId },
+    /// Withdraws the initial deposit from the new account.
+    Withdraw {
+        account_id: AccountId,
+        amount: Amount,
+    },
+}
+
+/// Outcome of the last run of a transition.
+#[derive(strum::Display, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PhaseTransitionStatus {
+    /// Running, or failed after the positions were closed. The next run resumes the attempt.
+    InProgress,
+    /// Failed and rolled back. The next run starts a new attempt with new Idempotency-Keys.
+    Compensated,
+    /// A compensation failed. Further runs are refused until the accounts are fixed manually.
+    CompensationFailed,
+    /// Finished. Further runs return the saved summary without calling the api.
+    Completed,
+}
+
+/// Progress of a transition. Saved before and after every run.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTransitionState {
+    /// Number of the attempt starting from 1. Idempotency-Keys are derived from it, so
+    /// a compensated attempt is never replayed.
+    pub attempt: u32,
+    pub status: PhaseTransitionStatus,
+    /// Outcome of the completed transition.
+    #[serde(default)]
+    pub summary: Option<PhaseTransitionSummary>,
+}
+
+impl Default for PhaseTransitionState {
+    fn default() -> Self {
+        Self {
+            attempt: 1,
+            status: PhaseTransitionStatus::InProgress,
+            summary: None,
+        }
+    }
+}
+
+/// Persists transition progress by transition id. Implement it on top of a database
+/// to keep the attempts across restarts.
+#[async_trait::async_trait]
+pub trait PhaseTransitionStore {
+    async fn load(&self, transition_id: &str) -> Result<Option<PhaseTransitionState>, String>;
+    async fn save(&self, transition_id: &str, state: &PhaseTransitionState) -> Result<(), String>;
+}
+
+/// Default store. Progress is kept for the lifetime of the process only.
+#[derive(Default)]
+pub struct InMemoryPhaseTransitionStore {
+    states: Mutex<HashMap<String, PhaseTransitionState>>,
+}
+
+#[async_trait::async_trait]
+impl PhaseTransitionStore for InMemoryPhaseTransitionStore {
+    async fn load(&self, transition_id: &str) -> Result<Option<PhaseTransitionState>, String> {
+        Ok(self.states.lock().unwrap().get(transition_id).cloned())
+    }
+
+    async fn save(&self, transition_id: &str, state: &PhaseTransitionState) -> Result<(), String> {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(transition_id.to_string(), state.clone());
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PhaseTransitionRequest {
+    /// Business id of the transition. Idempotency-Keys of the account creation, the deposit and
+    /// the compensating withdrawal are derived from it and the attempt number, so a retry must
+    /// use the same id.
+    pub transition_id: String,
+    /// The evaluation account.
+    pub account_id: AccountId,
+    /// Name of the new account.
+    pub account_name: String,
+    /// Group of the new account.
+    pub group_id: GroupId,
+    pub initial_deposit: Option<Amount>,
+    pub archive_mode: ArchiveMode,
+}
+
+impl PhaseTransitionRequest {
+    pub fn new(
+        transition_id: impl Into<String>,
+        account_id: impl Into<AccountId>,
+        account_name: impl Into<String>,
+        group_id: impl Into<GroupId>,
+    ) -> Self {
+        Self {
+            transition_id: transition_id.into(),
+            account_id: account_id.into(),
+            account_name: account_name.into(),
+            group_id: group_id.into(),
+            initial_deposit: None,
+            archive_mode: ArchiveMode::Suspend,
+        }
+    }
+
+    pub fn with_initial_deposit(mut self, amount: Amount) -> Self {
+        self.initial_deposit = Some(amount);
+        self
+    }
+
+    pub fn with_archive_mode(mut self, archive_mode: ArchiveMode) -> Self {
+        self.archive_mode = archive_mode;
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PhaseTransitionSummary {
+    pub transition_id: String,
+    pub attempt: u32,
+    pub old_account_id: AccountId,
+    pub new_account_id: AccountId,
+    pub deposit_operation_id: Option<OperationId>,
+}
+
+/// Failed transition with the outcome of the compensations run for the completed steps.
+#[derive(Debug)]
+pub struct PhaseTransitionError {
+    pub step: PhaseTransitionStep,
+    pub error: Error,
+    /// Compensations in the order they ran, with the error if one failed.
+    pub compensations: Vec<(PhaseTransitionCompensation, Option<Error>)>,
+    /// The close of the old account's positions was requested, so nothing was compensated.
+    /// Run the transition again with the same id to finish it.
+    pub positions_closed: bool,
+    /// Failed to save the outcome to the store. A retry may resume the failed attempt.
+    pub store_error: Option<String>,
+}
+
+impl PhaseTransitionError {
+    /// Whether every completed step was undone. If not, the transition must be finished
+    /// or the accounts need manual attention.
+    pub fn is_compensated(&self) -> bool {
+        !self.positions_closed && self.compensations.iter().all(|(_, error)| error.is_none())
+    }
+}
+
+impl fmt::Display for PhaseTransitionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.positions_closed {
+            return write!(
+                f,
+                "phase transition failed at {} after closing positions: {}",
+                self.step, self.error
+            );
+        }
+
+        let failed = self
+            .compensations
+            .iter()
+            .filter(|(_, error)| error.is_some())
+            .count();
+
+        write!(
+            f,
+            "phase transition failed at {}: {}; {} of {} compensations failed",
+            self.step,
+            self.error,
+            failed,
+            self.compensations.len()
+        )
+    }
+}
+
+impl std::error::Error for PhaseTransitionError {}
+
+/// Runs phase transitions on top of BrandApiClient.
+pub struct BrandApiPhaseTransition<C: BrandApiConfig> {
+    client: Arc<BrandApiClient<C>>,
+    store: Arc<dyn PhaseTransitionStore + Send + Sync>,
+    positions_close_timeout: Duration,
+    positions_poll_interval: Duration,
+}
+
+impl<C: BrandApiConfig> BrandApiPhaseTransition<C> {
+    pub fn new(client: Arc<BrandApiClient<C>>) -> Self {
+        Self {
+            client,
+            store: Arc::new(InMemoryPhaseTransitionStore::default()),
+            positions_close_timeout: DEFAULT_POSITIONS_CLOSE_TIMEOUT,
+            positions_poll_interval: DEFAULT_POSITIONS_POLL_INTERVAL,
+        }
+    }
+
+    /// Replaces the default in-memory store.
+    pub fn with_store(mut self, store: Arc<dyn PhaseTransitionStore + Send + Sync>) -> Self {
+        self.store = store;
+        self
+    }
+
+    pub fn with_positions_close_timeout(mut self, timeout: Duration) -> Self {
+        self.positions_close_timeout = timeout;
+        self
+    }
+
+    pub fn with_positions_poll_interval(mut self, interval: Duration) -> Self {
+        self.positions_poll_interval = interval;
+        self
+    }
+
+    pub async fn get_state(
+        &self,
+        transition_id: &str,
+    ) -> Result<Option<PhaseTransitionState>, Error> {
+        Ok(self.store.load(transition_id).await?)
+    }
+
+    /// Runs the transition. On an error before the positions are closed the completed steps are
+    /// compensated in reverse order and the next run starts a new attempt. On a later error
+    /// the next run resumes the attempt. Once completed, the saved summary is returned.
+    pub async fn transition(
+        &self,
+        request: &PhaseTransitionRequest,
+    ) -> Result<PhaseTransitionSummary, PhaseTransitionError> {
+        let transition_id = request.transition_id.as_str();
+        let fail = |step, error| PhaseTransitionError {
+            step,
+            error,
+            compensations: vec![],
+            positions_closed: false,
+            store_error: None,
+        };
+        let mut state = self
+            .get_state(transition_id)
+            .await
+            .map_err(|err| fail(PhaseTransitionStep::LoadState, err))?
+            .unwrap_or_default();
+
+        match state.status {
+            PhaseTransitionStatus::InProgress => {}
+            PhaseTransitionStatus::Completed => {
+                let err = format!(
+                    "transition {} is completed without a saved summary",
+                    transition_id
+                );
+
+                return state
+                    .summary
+                    .ok_or_else(|| fail(PhaseTransitionStep::LoadState, err.into()));
+            }
+            PhaseTransitionStatus::Compensated => {
+                state.attempt += 1;
+                state.status = PhaseTransitionStatus::InProgress;
+            }
+            PhaseTransitionStatus::CompensationFailed => {
+                let err = format!(
+                    "attempt {} of transition {} was not fully compensated",
+                    state.attempt, transition_id
+                );
+                return Err(fail(PhaseTransitionStep::LoadState, err.into()));
+            }
+        }
+
+        self.store
+            .save(transition_id, &state)
+            .await
+            .map_err(|err| fail(PhaseTransitionStep::LoadState, err.into()))?;
+
+        let mut run = PhaseTransitionRun {
+            attempt_id: format!("{}#{}", transition_id, state.attempt),
+            compensations: vec![],
+            positions_closed: false,
+        };
+        let result = self.run(request, state.attempt, &mut run).await;
+
+        let (step, error) = match result {
+            Ok(summary) => {
+                state.status = PhaseTransitionStatus::Completed;
+                state.summary = Some(summary.clone());
+                // the transition is complete, a failed save only lets a retry replay it
+                _ = self.store.save(transition_id, &state).await;
+
+                return Ok(summary);
+            }
+            Err(err) => err,
+        };
+
+        if run.positions_closed {
+            return Err(PhaseTransitionError {
+                positions_closed: true,
+                ..fail(step, error)
+            });
+        }
+
+        let mut outcomes = vec![];
+
+        for compensation in run.compensations.into_iter().rev() {
+            let result = self.compensate(&run.attempt_id, &compensation).await;
+            outcomes.push((compensation, result.err()));
+        }
+
+        let mut err = PhaseTransitionError {
+            compensations: outcomes,
+            ..fail(step, error)
+        };
+        state.status = if err.is_compensated() {
+            PhaseTransitionStatus::Compensated
+        } else {
+            PhaseTransitionStatus::CompensationFailed
+        };
+        err.store_error = self.store.save(transition_id, &state).await.err();
+
+        Err(err)
+    }
+
+    async fn run(
+        &self,
+        request: &PhaseTransitionRequest,
+        attempt: u32,
+        run: &mut PhaseTransitionRun,
+    ) -> Result<PhaseTransitionSummary, (PhaseTransitionStep, Error)> {
+        use PhaseTransitionStep as Step;
+        let client = &self.client;
+        let attempt_id = run.attempt_id.clone();
+
+        let old_account = client
+            .get_account(&GetAccountRequest::new(request.account_id.clone()))
+            .await
+            .map_err(|err| (Step::LoadAccount, err))?;
+
+        let account_request = CreateAccountRequest::new(
+            old_account.user_id.clone(),
+            request.account_name.clone(),
+            AccountType::Live,
+            old_account.currency.clone(),
+        );
+        let new_account = client
+            .create_account_idempotent(&account_request, Some(&attempt_id))
+            .await
+            .map_err(|err| (Step::CreateAccount, err))?
+            .data;
+        run.compensations
+            .push(PhaseTransitionCompensation::SuspendAccount {
+                account_id: new_account.account_id.clone(),
+            });
+
+        client
+            .set_account_group(&SetAccountGroupRequest::new(
+                new_account.account_id.clone(),
+                request.group_id.clone(),
+            ))
+            .await
+            .map_err(|err| (Step::SetAccountGroup, err))?;
+
+        let mut deposit_operation_id = None;
+
+        if let Some(amount) = &request.initial_deposit {
+            let deposit_request =
+                AccountOperationRequest::new(new_account.account_id.clone(), amount.clone());
+            let resp = client
+                .deposit_account_idempotent(&deposit_request, Some(&attempt_id))
+                .await
+                .map_err(|err| (Step::Deposit, err))?;
+            deposit_operation_id = Some(resp.data.operation_id);
+            run.compensations
+                .push(PhaseTransitionCompensation::Withdraw {
+                    account_id: new_account.account_id.clone(),
+                    amount: amount.clone(),
+                });
+        }
+
+        let old_status_request = UpdateAccountStatusRequest::new(old_account.account_id.clone());
+        client
+            .restrict_account(&old_status_request)
+            .await
+            .map_err(|err| (Step::FreezeAccount, err))?;
+        run.compensations
+            .push(PhaseTransitionCompensation::RestoreAccountStatus {
+                account_id: old_account.account_id.clone(),
+                status: old_account.status.clone(),
+            });
+
+        // a failed close request may have closed some of the positions, so nothing is
+        // compensated from here on
+        run.positions_closed = true;
+        client
+            .close_account_positions(&CloseAccountPositionsRequest::new(
+                old_account.account_id.clone(),
+            ))
+            .await
+            .map_err(|err| (Step::ClosePositions, err))?;
+        self.wait_positions_closed(&old_account)
+            .await
+            .map_err(|err| (Step::WaitPositionsClosed, err))?;
+
+        // with ArchiveMode::Restrict the account is already left restricted by the freeze step
+        if request.archive_mode == ArchiveMode::Suspend {
+            client
+                .suspend_account(&old_status_request)
+                .await
+                .map_err(|err| (Step::ArchiveAccount, err))?;
+        }
+
+        Ok(PhaseTransitionSummary {
+            transition_id: request.transition_id.clone(),
+            attempt,
+            old_account_id: old_account.account_id,
+            new_account_id: new_account.account_id,
+            deposit_operation_id,
+        })
+    }
+
+    async fn wait_positions_closed(&self, account: &AccountModel) -> Result<(), Error> {
+        let request = GetOpenedPositionsRequest::builder(account.account_type.clone())
+            .with_account_id(account.account_id.clone())
+            .build()?;
+        let deadline = Instant::now() + self.positions_close_timeout;
+
+        loop {
+            let positions = self.client.get_opened_positions(&request).await?;
+
+            if positions.data.is_empty() {
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(format!(
+                    "{} positions of account {} are still open after {:?}",
+                    positions.data.len(),
+                    account.account_id,
+                    self.positions_close_timeout
+                )
+                .into());
+            }
+
+            tokio::time::sleep(self.positions_poll_interval).await;
+        }
+    }
+
+    async fn compensate(
+        &self,
+        attempt_id: &str,
+        compensation: &PhaseTransitionCompensation,
+    ) -> Result<(), Error> {
+        let client = &self.client;
+
+        match compensation {
+            PhaseTransitionCompensation::RestoreAccountStatus { account_id, status } => {
+                let status_request = UpdateAccountStatusRequest::new(account_id.clone());
+
+                match status {
+                    AccountStatus::Active => client.activate_account(&status_request).await?,
+                    AccountStatus::Restricted => client.restrict_account(&status_request).await?,
+                    AccountStatus::Suspended => client.suspend_account(&status_request).await?,
+                };
+            }
+            PhaseTransitionCompensation::SuspendAccount { account_id } => {
+                client
+                    .suspend_account(&UpdateAccountStatusRequest::new(account_id.clone()))
+                    .await?;
+            }
+            PhaseTransitionCompensation::Withdraw { account_id, amount } => {
+                let withdraw_request =
+                    AccountOperationRequest::new(account_id.clone(), amount.clone());
+                client
+                    .withdraw_account_idempotent(&withdraw_request, Some(attempt_id))
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Progress of a single run, needed to undo it.
+struct PhaseTransitionRun {
+    /// Business id of the Idempotency-Keys: the transition id and the attempt number.
+    attempt_id: String,
+    compensations: Vec<PhaseTransitionCompensation>,
+    positions_closed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand::mock_server::MockBrandApi;
+    use crate::brand::test_support::{get_client, get_opened_position, TestConfig};
+    use crate::brand::{CreateUserRequest, GroupModel};
+
+    async fn setup() -> (
+        Arc<MockBrandApi>,
+        BrandApiPhaseTransition<TestConfig>,
+        AccountId,
+    ) {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let client = Arc::new(get_client(&api));
+        let user = client
+            .create_user(
+                &CreateUserRequest::new("trader@example.com", "Qwerty!123"),
+                None,
+            )
+            .await
+            .unwrap();
+        let account = client
+            .create_account(
+                &CreateAccountRequest::new(user.user_id, "evaluation", AccountType::Live, "USD"),
+                None,
+            )
+            .await
+            .unwrap();
+        api.add_opened_position(&get_opened_position("1", &account.account_id));
+        let transition = BrandApiPhaseTransition::new(client)
+            .with_positions_poll_interval(Duration::from_millis(1));
+
+        (api, transition, account.account_id)
+    }
+
+    #[tokio::test]
+    async fn moves_trader_to_funded_account() {
+        let (api, transition, account_id) = setup().await;
+        api.add_group(&GroupModel {
+            name: "funded".to_string(),
+            id: "2".into(),
+        });
+        let request = PhaseTransitionRequest::new("phase-1", account_id.clone(), "funded", "2")
+            .with_initial_deposit("50000".parse().unwrap());
+
+        let summary = transition.transition(&request).await.unwrap();
+
+        assert_eq!(api.get_status(&account_id).as_deref(), Some("SUSPENDED"));
+        let new_account = transition
+            .client
+            .get_account(&GetAccountRequest::new(summary.new_account_id.clone()))
+            .await
+            .unwrap();
+        assert_eq!(new_account.user_group_id, "2");
+        assert_eq!(api.get_balance(&summary.new_account_id), Some(50000.0));
+        assert!(summary.deposit_operation_id.is_some());
+    }
+
+    #[tokio::test]
+    async fn returns_summary_of_completed_transition() {
+        let (api, transition, account_id) = setup().await;
+        api.add_group(&GroupModel {
+            name: "funded".to_string(),
+            id: "2".into(),
+        });
+        let request = PhaseTransitionRequest::new("phase-1", account_id.clone(), "funded", "2")
+            .with_initial_deposit("50000".parse().unwrap());
+        let summary = transition.transition(&request).await.unwrap();
+        let requests_count = api.get_requests_count();
+
+        let again = transition.transition(&request).await.unwrap();
+
+        assert_eq!(again, summary);
+        assert_eq!(api.get_requests_count(), requests_count);
+        assert_eq!(api.get_status(&account_id).as_deref(), Some("SUSPENDED"));
+        assert_eq!(api.get_accounts_count(), 2);
+        assert_eq!(api.get_balance(&summary.new_account_id), Some(50000.0));
+    }
+
+    #[tokio::test]
+    async fn compensates_failed_transition() {
+        let (api, transition, account_id) = setup().await;
+        // the group does not exist, so the new account cannot be moved into it
+        let request = PhaseTransitionRequest::new("phase-1", account_id.clone(), "funded", "2");
+
+        let err = transition.transition(&request).await.unwrap_err();
+
+        assert_eq!(err.step, PhaseTransitionStep::SetAccountGroup);
+        assert!(err.is_compensated());
+        assert!(matches!(
+            err.compensations.as_slice(),
+            [(PhaseTransitionCompensation::SuspendAccount { .. }, None)]
+        ));
+        assert_eq!(api.get_status(&account_id).as_deref(), Some("ACTIVE"));
+        assert_eq!(api.get_opened_positions_count(&account_id), 1);
+        assert_eq!(api.get_accounts_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn retries_compensated_transition_with_new_attempt() {
+        let (api, transition, account_id) = setup().await;
+        let request = PhaseTransitionRequest::new("phase-1", account_id.clone(), "funded", "2")
+            .with_initial_deposit("50000".parse().unwrap());
+        transition.transition(&request).await.unwrap_err();
+        api.add_group(&GroupModel {
+            name: "funded".to_string(),
+            id: "2".into(),
+        });
+
+        let summary = transition.transition(&request).await.unwrap();
+
+        assert_eq!(summary.attempt, 2);
+        assert_eq!(api.get_accounts_count(), 3);
+        let new_account_id = &summary.new_account_id;
+        assert_eq!(api.get_status(new_account_id).as_deref(), Some("ACTIVE"));
+        assert_eq!(api.get_balance(new_account_id), Some(50000.0));
+        assert_eq!(api.get_opened_positions_count(&account_id), 0);
+        assert_eq!(
+            transition.get_state("phase-1").await.unwrap(),
+            Some(PhaseTransitionState {
+                attempt: 2,
+                status: PhaseTransitionStatus::Completed,
+                summary: Some(summary),
+            })
+        );
+    }
+}
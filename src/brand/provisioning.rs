@@ -0,0 +1,386 @@
+//! Provisioning of a trader account: user, account, group, initial deposit and activation.
+//! Progress is saved after every step and all non-idempotent calls use Idempotency-Keys derived
+//! from the provisioning id, so a failed or crashed provisioning can simply be run again.
+
+use crate::brand::api_client::{BrandApiClient, BrandApiConfig};
+use crate::brand::errors::{BrandApiError, Error};
+use crate::brand::{
+    AccountModel, AccountOperationRequest, CheckEmailRequest, CreateAccountRequest,
+    CreateUserRequest, GetAccountRequest, SetAccountGroupRequest, UpdateAccountStatusRequest,
+};
+use crate::models::{AccountId, AccountType, Amount, GroupId, OperationId, UserId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Steps of a provisioning in the order they run.
+#[derive(
+    strum::Display, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+pub enum ProvisioningStep {
+    /// Finds the user by email or creates it.
+    User,
+    Account,
+    /// Moves the account into the requested group. Skipped without a group.
+    Group,
+    /// Funds the account with the initial deposit. Skipped without a deposit.
+    Deposit,
+    Activation,
+}
+
+/// Progress of a provisioning. Saved after every completed step.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProvisioningState {
+    pub user_id: Option<UserId>,
+    /// False if a user with the email already existed.
+    pub user_created: bool,
+    pub account_id: Option<AccountId>,
+    pub deposit_operation_id: Option<OperationId>,
+    pub completed_steps: Vec<ProvisioningStep>,
+}
+
+impl ProvisioningState {
+    pub fn is_completed(&self, step: ProvisioningStep) -> bool {
+        self.completed_steps.contains(&step)
+    }
+
+    fn complete(&mut self, step: ProvisioningStep) {
+        if !self.is_completed(step) {
+            self.completed_steps.push(step);
+        }
+    }
+}
+
+/// Persists provisioning progress by provisioning id. Implement it on top of a database
+/// to resume provisionings interrupted by a restart.
+#[async_trait::async_trait]
+pub trait ProvisioningStore {
+    async fn load(&self, provisioning_id: &str) -> Result<Option<ProvisioningState>, String>;
+    async fn save(&self, provisioning_id: &str, state: &ProvisioningState) -> Result<(), String>;
+}
+
+/// Default store. Progress is kept for the lifetime of the process only.
+#[derive(Default)]
+pub struct InMemoryProvisioningStore {
+    states: Mutex<HashMap<String, ProvisioningState>>,
+}
+
+#[async_trait::async_trait]
+impl ProvisioningStore for InMemoryProvisioningStore {
+    async fn load(&self, provisioning_id: &str) -> Result<Option<ProvisioningState>, String> {
+        Ok(self.states.lock().unwrap().get(provisioning_id).cloned())
+    }
+
+    async fn save(&self, provisioning_id: &str, state: &ProvisioningState) -> Result<(), String> {
+        self.states
+            .lock()
+            .unwrap()
+            .insert(provisioning_id.to_string(), state.clone());
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ProvisioningRequest {
+    /// Business id of the provisioning, e.g. the challenge purchase id. Retries must use
+    /// the same id and the same request, otherwise duplicates are not detected.
+    pub provisioning_id: String,
+    /// Used to create the user if no user with the email exists.
+    pub user: CreateUserRequest,
+    pub account_name: String,
+    pub account_type: AccountType,
+    /// The 3-letter ISO 4217 code of the account currency.
+    pub currency: String,
+    /// The account stays in the brand's default group if not set.
+    pub group_id: Option<GroupId>,
+    pub initial_deposit: Option<Amount>,
+}
+
+impl ProvisioningRequest {
+    pub fn new(
+        provisioning_id: impl Into<String>,
+        user: CreateUserRequest,
+        account_name: impl Into<String>,
+        account_type: AccountType,
+        currency: impl Into<String>,
+    ) -> Self {
+        Self {
+            provisioning_id: provisioning_id.into(),
+            user,
+            account_name: account_name.into(),
+            account_type,
+            currency: currency.into(),
+            group_id: None,
+            initial_deposit: None,
+        }
+    }
+
+    pub fn with_group_id(mut self, group_id: impl Into<GroupId>) -> Self {
+        self.group_id = Some(group_id.into());
+        self
+    }
+
+    pub fn with_initial_deposit(mut self, amount: Amount) -> Self {
+        self.initial_deposit = Some(amount);
+        self
+    }
+}
+
+#[derive(Debug)]
+pub struct ProvisioningSummary {
+    pub provisioning_id: String,
+    pub user_id: UserId,
+    /// False if a user with the email already existed.
+    pub user_created: bool,
+    /// The account after activation.
+    pub account: AccountModel,
+    pub deposit_operation_id: Option<OperationId>,
+}
+
+/// Runs provisionings on top of BrandApiClient.
+pub struct BrandApiProvisioner<C: BrandApiConfig> {
+    client: Arc<BrandApiClient<C>>,
+    store: Arc<dyn ProvisioningStore + Send + Sync>,
+}
+
+impl<C: BrandApiConfig> BrandApiProvisioner<C> {
+    pub fn new(client: Arc<BrandApiClient<C>>) -> Self {
+        Self {
+            client,
+            store: Arc::new(InMemoryProvisioningStore::default()),
+        }
+    }
+
+    /// Replaces the default in-memory store.
+    pub fn with_store(mut self, store: Arc<dyn ProvisioningStore + Send + Sync>) -> Self {
+        self.store = store;
+        self
+    }
+
+    /// Saved progress of the provisioning, None if it never ran.
+    pub async fn get_state(
+        &self,
+        provisioning_id: &str,
+    ) -> Result<Option<ProvisioningState>, Error> {
+        Ok(self.store.load(provisioning_id).await?)
+    }
+
+    /// Runs the steps not completed by previous runs with the same provisioning id.
+    /// On error the completed steps stay saved and the next run resumes from the failed one.
+    pub async fn provision(
+        &self,
+        request: &ProvisioningRequest,
+    ) -> Result<ProvisioningSummary, Error> {
+        let provisioning_id = request.provisioning_id.as_str();
+        let mut state = self.get_state(provisioning_id).await?.unwrap_or_default();
+
+        if !state.is_completed(ProvisioningStep::User) {
+            let (user_id, user_created) = self.find_or_create_user(request).await?;
+            state.user_id = Some(user_id);
+            state.user_created = user_created;
+            self.complete(provisioning_id, &mut state, ProvisioningStep::User)
+                .await?;
+        }
+
+        let user_id = state
+            .user_id
+            .clone()
+            .ok_or("provisioning state has no user id")?;
+
+        if !state.is_completed(ProvisioningStep::Account) {
+            let account_request = CreateAccountRequest::new(
+                user_id.clone(),
+                request.account_name.clone(),
+                request.account_type.clone(),
+                request.currency.clone(),
+            );
+            let resp = self
+                .client
+                .create_account_idempotent(&account_request, Some(provisioning_id))
+                .await?;
+            state.account_id = Some(resp.data.account_id);
+            self.complete(provisioning_id, &mut state, ProvisioningStep::Account)
+                .await?;
+        }
+
+        let account_id = state
+            .account_id
+            .clone()
+            .ok_or("provisioning state has no account id")?;
+
+        if !state.is_completed(ProvisioningStep::Group) {
+            if let Some(group_id) = &request.group_id {
+                self.client
+                    .set_account_group(&SetAccountGroupRequest::new(
+                        account_id.clone(),
+                        group_id.clone(),
+                    ))
+                    .await?;
+            }
+
+            self.complete(provisioning_id, &mut state, ProvisioningStep::Group)
+                .await?;
+        }
+
+        if !state.is_completed(ProvisioningStep::Deposit) {
+            if let Some(amount) = &request.initial_deposit {
+                let deposit_request =
+                    AccountOperationRequest::new(account_id.clone(), amount.clone());
+                let resp = self
+                    .client
+                    .deposit_account_idempotent(&deposit_request, Some(provisioning_id))
+                    .await?;
+                state.deposit_operation_id = Some(resp.data.operation_id);
+            }
+
+            self.complete(provisioning_id, &mut state, ProvisioningStep::Deposit)
+                .await?;
+        }
+
+        if !state.is_completed(ProvisioningStep::Activation) {
+            self.client
+                .activate_account(&UpdateAccountStatusRequest::new(account_id.clone()))
+                .await?;
+            self.complete(provisioning_id, &mut state, ProvisioningStep::Activation)
+                .await?;
+        }
+
+        let account = self
+            .client
+            .get_account(&GetAccountRequest::new(account_id))
+            .await?;
+
+        Ok(ProvisioningSummary {
+            provisioning_id: provisioning_id.to_string(),
+            user_id,
+            user_created: state.user_created,
+            account,
+            deposit_operation_id: state.deposit_operation_id,
+        })
+    }
+
+    async fn find_or_create_user(
+        &self,
+        request: &ProvisioningRequest,
+    ) -> Result<(UserId, bool), Error> {
+        let check_request = CheckEmailRequest::new(request.user.email.clone());
+
+        match self.client.check_email(&check_request).await {
+            Ok(resp) => return Ok((resp.user_id, false)),
+            Err(err) if matches!(err.as_api_error(), Some(BrandApiError::NotFound(_))) => {}
+            Err(err) => return Err(err),
+        }
+
+        let resp = self
+            .client
+            .create_user_idempotent(&request.user, Some(&request.provisioning_id))
+            .await?;
+
+        Ok((resp.data.user_id, true))
+    }
+
+    async fn complete(
+        &self,
+        provisioning_id: &str,
+        state: &mut ProvisioningState,
+        step: ProvisioningStep,
+    ) -> Result<(), Error> {
+        state.complete(step);
+
+        Ok(self.store.save(provisioning_id, state).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand::mock_server::MockBrandApi;
+    use crate::brand::test_support::{get_client, TestConfig};
+    use crate::brand::GroupModel;
+
+    fn get_provisioner(api: &Arc<MockBrandApi>) -> BrandApiProvisioner<TestConfig> {
+        BrandApiProvisioner::new(Arc::new(get_client(api)))
+    }
+
+    fn get_request(group_id: &str) -> ProvisioningRequest {
+        ProvisioningRequest::new(
+            "purchase-1",
+            CreateUserRequest::new("trader@example.com", "Qwerty!123"),
+            "Challenge 100k",
+            AccountType::Live,
+            "USD",
+        )
+        .with_group_id(group_id)
+        .with_initial_deposit("100000".parse().unwrap())
+    }
+
+    fn add_group(api: &MockBrandApi, id: &str) {
+        api.add_group(&GroupModel {
+            name: "evaluation".to_string(),
+            id: id.into(),
+        });
+    }
+
+    #[tokio::test]
+    async fn provisions_account() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        add_group(&api, "2");
+        let provisioner = get_provisioner(&api);
+
+        let summary = provisioner.provision(&get_request("2")).await.unwrap();
+
+        assert!(summary.user_created);
+        assert_eq!(summary.account.user_group_id, "2");
+        assert!(summary.deposit_operation_id.is_some());
+        assert_eq!(api.get_balance(&summary.account.account_id), Some(100000.0));
+        assert_eq!(
+            api.get_status(&summary.account.account_id).as_deref(),
+            Some("ACTIVE")
+        );
+    }
+
+    #[tokio::test]
+    async fn resumes_failed_provisioning() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        let provisioner = get_provisioner(&api);
+
+        // the group does not exist yet, so the group step fails
+        provisioner.provision(&get_request("2")).await.unwrap_err();
+        let state = provisioner.get_state("purchase-1").await.unwrap().unwrap();
+        assert_eq!(
+            state.completed_steps,
+            vec![ProvisioningStep::User, ProvisioningStep::Account]
+        );
+
+        add_group(&api, "2");
+        let summary = provisioner.provision(&get_request("2")).await.unwrap();
+        provisioner.provision(&get_request("2")).await.unwrap();
+
+        assert_eq!(Some(summary.account.account_id.clone()), state.account_id);
+        assert_eq!(api.get_accounts_count(), 1);
+        assert_eq!(api.get_balance(&summary.account.account_id), Some(100000.0));
+    }
+
+    #[tokio::test]
+    async fn does_not_duplicate_after_lost_progress() {
+        let api = Arc::new(MockBrandApi::new("key"));
+        add_group(&api, "2");
+
+        // a new provisioner has no saved progress, as after a restart with the in-memory store
+        let first = get_provisioner(&api)
+            .provision(&get_request("2"))
+            .await
+            .unwrap();
+        let second = get_provisioner(&api)
+            .provision(&get_request("2"))
+            .await
+            .unwrap();
+
+        assert!(!second.user_created);
+        assert_eq!(first.user_id, second.user_id);
+        assert_eq!(first.account.account_id, second.account.account_id);
+        assert_eq!(first.deposit_operation_id, second.deposit_operation_id);
+        assert_eq!(api.get_balance(&first.account.account_id), Some(100000.0));
+    }
+}
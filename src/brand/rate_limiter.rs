@@ -0,0 +1,283 @@
+use crate::brand::endpoints::BrandApiEndpoint;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{Mutex, OwnedSemaphorePermit, Semaphore};
+use tokio::time::Instant;
+
+/// Token bucket budget: up to `requests` calls per `per`, refilled continuously.
+/// Zero requests are treated as one.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimit {
+    pub requests: u32,
+    pub per: Duration,
+}
+
+impl RateLimit {
+    pub fn per_second(requests: u32) -> Self {
+        Self {
+            requests: requests.max(1),
+            per: Duration::from_secs(1),
+        }
+    }
+
+    pub fn per_minute(requests: u32) -> Self {
+        Self {
+            requests: requests.max(1),
+            per: Duration::from_secs(60),
+        }
+    }
+
+    fn get_tokens_per_second(&self) -> f64 {
+        self.get_requests() as f64 / self.per.as_secs_f64()
+    }
+
+    fn get_requests(&self) -> u32 {
+        self.requests.max(1)
+    }
+}
+
+struct TokenBucket {
+    limit: RateLimit,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            tokens: limit.get_requests() as f64,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Takes a token or returns how long to wait for the next one.
+    fn try_take(&mut self) -> Result<(), Duration> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        let rate = self.limit.get_tokens_per_second();
+        self.tokens = (self.tokens + elapsed * rate).min(self.limit.get_requests() as f64);
+        self.last_refill = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) / rate))
+        }
+    }
+}
+
+/// Client side throttling for BrandApiClient: per-endpoint token buckets,
+/// a cap on requests in flight and a pause after 429 responses with Retry-After.
+pub struct BrandApiRateLimiter {
+    default_limit: Option<RateLimit>,
+    endpoint_limits: HashMap<BrandApiEndpoint, RateLimit>,
+    buckets: Mutex<HashMap<BrandApiEndpoint, TokenBucket>>,
+    in_flight: Option<Arc<Semaphore>>,
+    paused_until: Mutex<Option<Instant>>,
+}
+
+/// Keeps the in-flight slot taken until dropped.
+pub struct BrandApiRatePermit {
+    _permit: Option<OwnedSemaphorePermit>,
+}
+
+impl Default for BrandApiRateLimiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BrandApiRateLimiter {
+    /// Limiter without limits. Add them with the `with_*` methods.
+    pub fn new() -> Self {
+        Self {
+            default_limit: None,
+            endpoint_limits: HashMap::new(),
+            buckets: Default::default(),
+            in_flight: None,
+            paused_until: Default::default(),
+        }
+    }
+
+    /// Budget for every endpoint without its own limit. Each endpoint gets a separate bucket.
+    pub fn with_default_limit(mut self, limit: RateLimit) -> Self {
+        self.default_limit = Some(limit);
+        self
+    }
+
+    pub fn with_endpoint_limit(mut self, endpoint: BrandApiEndpoint, limit: RateLimit) -> Self {
+        self.endpoint_limits.insert(endpoint, limit);
+        self
+    }
+
+    /// Zero is treated as one, so that requests are never blocked forever.
+    pub fn with_max_in_flight(mut self, max_in_flight: usize) -> Self {
+        self.in_flight = Some(Arc::new(Semaphore::new(max_in_flight.max(1))));
+        self
+    }
+
+    /// Waits until a request to the endpoint is allowed.
+    pub async fn acquire(&self, endpoint: BrandApiEndpoint) -> BrandApiRatePermit {
+        self.wait_pause().await;
+        self.take_token(endpoint).await;
+
+        let permit = match &self.in_flight {
+            Some(semaphore) => Some(
+                semaphore
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("semaphore is never closed"),
+            ),
+            None => None,
+        };
+
+        BrandApiRatePermit { _permit: permit }
+    }
+
+    /// Holds back all requests for the duration, e.g. the Retry-After of a 429 response.
+    pub async fn pause(&self, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut paused_until = self.paused_until.lock().await;
+
+        if paused_until.is_none_or(|current| current < until) {
+            *paused_until = Some(until);
+        }
+    }
+
+    async fn wait_pause(&self) {
+        loop {
+            let paused_until = *self.paused_until.lock().await;
+
+            match paused_until {
+                Some(until) if until > Instant::now() => tokio::time::sleep_until(until).await,
+                _ => return,
+            }
+        }
+    }
+
+    async fn take_token(&self, endpoint: BrandApiEndpoint) {
+        let Some(limit) = self
+            .endpoint_limits
+            .get(&endpoint)
+            .copied()
+            .or(self.default_limit)
+        else {
+            return;
+        };
+
+        loop {
+            let result = self
+                .buckets
+                .lock()
+                .await
+                .entry(endpoint)
+                .or_insert_with(|| TokenBucket::new(limit))
+                .try_take();
+
+            match result {
+                Ok(()) => return,
+                Err(wait) => tokio::time::sleep(wait).await,
+            }
+        }
+    }
+}
+
+/// Parses Retry-After header value: delay in seconds or http date.
+pub fn parse_retry_after(value: &str) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delay = date.with_timezone(&chrono::Utc) - chrono::Utc::now();
+
+    Some(delay.to_std().unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_retry_after() {
+        assert_eq!(parse_retry_after("5"), Some(Duration::from_secs(5)));
+        assert_eq!(
+            parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        assert_eq!(parse_retry_after("soon"), None);
+    }
+
+    #[tokio::test]
+    async fn throttles_by_endpoint_budget() {
+        let limit = RateLimit {
+            requests: 2,
+            per: Duration::from_millis(100),
+        };
+        let limiter =
+            BrandApiRateLimiter::new().with_endpoint_limit(BrandApiEndpoint::GetAccount, limit);
+        let started = Instant::now();
+
+        for _ in 0..4 {
+            limiter.acquire(BrandApiEndpoint::GetAccount).await;
+        }
+
+        assert!(started.elapsed() >= Duration::from_millis(90));
+
+        let started = Instant::now();
+        limiter.acquire(BrandApiEndpoint::GetOrders).await;
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn waits_for_pause() {
+        let limiter = BrandApiRateLimiter::new();
+        limiter.pause(Duration::from_millis(100)).await;
+        let started = Instant::now();
+
+        limiter.acquire(BrandApiEndpoint::GetAccount).await;
+
+        assert!(started.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn treats_zero_limits_as_one() {
+        let limiter = BrandApiRateLimiter::new()
+            .with_default_limit(RateLimit {
+                requests: 0,
+                per: Duration::from_millis(50),
+            })
+            .with_max_in_flight(0);
+        assert_eq!(RateLimit::per_second(0).requests, 1);
+
+        let acquire = async {
+            limiter.acquire(BrandApiEndpoint::GetAccount).await;
+            limiter.acquire(BrandApiEndpoint::GetAccount).await;
+        };
+        tokio::time::timeout(Duration::from_secs(1), acquire)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn caps_requests_in_flight() {
+        let limiter = BrandApiRateLimiter::new().with_max_in_flight(1);
+        let permit = limiter.acquire(BrandApiEndpoint::GetAccount).await;
+
+        let second = tokio::time::timeout(
+            Duration::from_millis(50),
+            limiter.acquire(BrandApiEndpoint::GetAccount),
+        )
+        .await;
+        assert!(second.is_err());
+
+        drop(permit);
+        limiter.acquire(BrandApiEndpoint::GetAccount).await;
+    }
+}
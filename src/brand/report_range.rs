@@ -0,0 +1,172 @@
+//! Splitting of long report ranges into windows accepted by the Brand API.
+
+use crate::brand::errors::BrandApiValidationError;
+use chrono::{DateTime, TimeDelta, TimeZone, Utc};
+use std::collections::HashSet;
+use std::hash::Hash;
+
+/// Maximum range between startDateTime and endDateTime of the closed trades and
+/// closed positions reports.
+pub const MAX_REPORT_WINDOW: TimeDelta = TimeDelta::days(31);
+
+/// Inclusive range of report dates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReportDateRange {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl ReportDateRange {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        Self { start, end }
+    }
+
+    /// The last `days` days up to now.
+    pub fn last_days(days: u32) -> Self {
+        let end = Utc::now();
+
+        Self {
+            start: end - TimeDelta::days(days as i64),
+            end,
+        }
+    }
+
+    /// The calendar month in UTC, from its first millisecond to its last.
+    /// Fails if `month` is not in 1..=12.
+    pub fn month(year: i32, month: u32) -> Result<Self, BrandApiValidationError> {
+        let (next_year, next_month) = if month == 12 {
+            (year + 1, 1)
+        } else {
+            (year, month + 1)
+        };
+        let get_start = |year, month| {
+            Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0)
+                .single()
+                .ok_or_else(|| {
+                    BrandApiValidationError::InvalidMonth(format!("{:04}-{:02}", year, month))
+                })
+        };
+
+        Ok(Self {
+            start: get_start(year, month)?,
+            end: get_start(next_year, next_month)? - TimeDelta::milliseconds(1),
+        })
+    }
+}
+
+/// Splits `[start, end]` into consecutive windows no longer than `max_window`.
+/// The end of a window is the start of the next one. Returns no windows if start is after end.
+pub fn split_date_range(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    max_window: TimeDelta,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    if start > end {
+        return vec![];
+    }
+
+    if max_window <= TimeDelta::zero() {
+        return vec![(start, end)];
+    }
+
+    let mut windows = vec![];
+    let mut window_start = start;
+
+    loop {
+        let window_end = (window_start + max_window).min(end);
+        windows.push((window_start, window_end));
+
+        if window_end >= end {
+            return windows;
+        }
+
+        window_start = window_end;
+    }
+}
+
+/// Drops items with an already seen key, keeping the first, and sorts the rest by date.
+/// The sort is stable, so items with the same date keep the order of the windows.
+pub(crate) fn merge_report_items<T, K: Eq + Hash>(
+    items: Vec<T>,
+    get_key: impl Fn(&T) -> K,
+    get_date: impl Fn(&T) -> DateTime<Utc>,
+) -> Vec<T> {
+    let mut keys = HashSet::new();
+    let mut items: Vec<T> = items
+        .into_iter()
+        .filter(|item| keys.insert(get_key(item)))
+        .collect();
+    items.sort_by_key(|item| get_date(item));
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> DateTime<Utc> {
+        format!("2024-01-{:02}T00:00:00Z", day).parse().unwrap()
+    }
+
+    #[test]
+    fn splits_range_into_windows() {
+        let windows = split_date_range(date(1), date(10), TimeDelta::days(4));
+
+        assert_eq!(
+            windows,
+            vec![(date(1), date(5)), (date(5), date(9)), (date(9), date(10))]
+        );
+    }
+
+    #[test]
+    fn keeps_short_range() {
+        assert_eq!(
+            split_date_range(date(1), date(2), MAX_REPORT_WINDOW),
+            vec![(date(1), date(2))]
+        );
+        assert_eq!(
+            split_date_range(date(1), date(1), MAX_REPORT_WINDOW),
+            vec![(date(1), date(1))]
+        );
+        assert!(split_date_range(date(2), date(1), MAX_REPORT_WINDOW).is_empty());
+    }
+
+    #[test]
+    fn builds_month_range() {
+        let range = ReportDateRange::month(2026, 12).unwrap();
+
+        assert_eq!(
+            range.start,
+            "2026-12-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(
+            range.end,
+            "2026-12-31T23:59:59.999Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert!(range.end - range.start < MAX_REPORT_WINDOW);
+    }
+
+    #[test]
+    fn rejects_invalid_month() {
+        assert_eq!(
+            ReportDateRange::month(2026, 13),
+            Err(BrandApiValidationError::InvalidMonth("2026-13".to_string()))
+        );
+        assert!(ReportDateRange::month(2026, 0).is_err());
+    }
+
+    #[test]
+    fn merges_items() {
+        let items = vec![
+            ("b", date(3)),
+            ("a", date(2)),
+            ("b", date(3)),
+            ("c", date(1)),
+        ];
+
+        let items = merge_report_items(items, |item| item.0, |item| item.1);
+
+        assert_eq!(items, vec![("c", date(1)), ("a", date(2)), ("b", date(3))]);
+    }
+}
@@ -0,0 +1,179 @@
+use crate::brand::endpoints::BrandApiEndpoint;
+use crate::brand::errors::BrandApiError;
+use crate::utils::get_random_f64;
+use std::time::Duration;
+
+/// Controls how BrandApiClient retries failed requests.
+///
+/// Endpoints which are not idempotent (create, deposit, withdraw, credit etc.) are retried
+/// only when the call carries an Idempotency-Key, unless `retry_non_idempotent` is set.
+#[derive(Debug, Clone)]
+pub struct BrandApiRetryPolicy {
+    /// Total number of attempts including the first one. 1 disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry.
+    pub initial_backoff: Duration,
+    /// Upper bound for a single delay.
+    pub max_backoff: Duration,
+    /// Factor the delay grows with after each attempt.
+    pub backoff_multiplier: f64,
+    /// Randomizes each delay within [delay / 2, delay].
+    pub jitter: bool,
+    pub retry_on_timeout: bool,
+    pub retry_on_transport_error: bool,
+    /// Retries 429 responses after their Retry-After delay. Like other errors, non-idempotent
+    /// endpoints are retried only with an Idempotency-Key.
+    pub retry_on_rate_limited: bool,
+    /// Response statuses which are worth retrying, e.g. 500 and 503.
+    pub retryable_status_codes: Vec<u16>,
+    /// Allows retrying non-idempotent endpoints without Idempotency-Key.
+    /// Can apply the same operation twice.
+    pub retry_non_idempotent: bool,
+}
+
+impl Default for BrandApiRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+            backoff_multiplier: 2.0,
+            jitter: true,
+            retry_on_timeout: true,
+            retry_on_transport_error: true,
+            retry_on_rate_limited: true,
+            retryable_status_codes: vec![500, 502, 503, 504],
+            retry_non_idempotent: false,
+        }
+    }
+}
+
+impl BrandApiRetryPolicy {
+    /// Single attempt. Used by BrandApiClient unless another policy is set.
+    pub fn no_retries() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// `attempt` is the number of the failed attempt starting from 1.
+    pub fn should_retry(
+        &self,
+        endpoint: BrandApiEndpoint,
+        has_idempotency_key: bool,
+        attempt: u32,
+        err: &BrandApiError,
+    ) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+
+        if !endpoint.is_idempotent() && !has_idempotency_key && !self.retry_non_idempotent {
+            return false;
+        }
+
+        match err {
+            BrandApiError::RateLimited(_) => self.retry_on_rate_limited,
+            BrandApiError::Timeout(_) => self.retry_on_timeout,
+            BrandApiError::Transport(_) => self.retry_on_transport_error,
+            BrandApiError::Deserialization(_) => false,
+            err => err
+                .status_code()
+                .is_some_and(|code| self.retryable_status_codes.contains(&code)),
+        }
+    }
+
+    /// Delay before the next attempt after the failed `attempt` starting from 1.
+    pub fn get_backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32) as i32;
+        let backoff = self.initial_backoff.as_secs_f64() * self.backoff_multiplier.powi(exponent);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+
+        if self.jitter {
+            Duration::from_secs_f64(backoff / 2.0 + backoff / 2.0 * get_random_f64())
+        } else {
+            Duration::from_secs_f64(backoff)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand::errors::BrandApiErrorDetails;
+    use http::Method;
+
+    fn get_error(status_code: Option<u16>) -> BrandApiError {
+        let details = BrandApiErrorDetails {
+            endpoint: BrandApiEndpoint::Deposit,
+            method: Method::POST,
+            url: String::new(),
+            request: None,
+            idempotency_key: None,
+            status_code,
+            body: None,
+            error_code: None,
+            retry_after: None,
+            error_response: None,
+            message: String::new(),
+        };
+
+        match status_code {
+            Some(code) => BrandApiError::from_status(code, details),
+            None => BrandApiError::Timeout(details),
+        }
+    }
+
+    #[test]
+    fn retries_idempotent_endpoints_only() {
+        let policy = BrandApiRetryPolicy::default();
+        let err = get_error(Some(503));
+
+        assert!(policy.should_retry(BrandApiEndpoint::GetAccount, false, 1, &err));
+        assert!(!policy.should_retry(BrandApiEndpoint::Deposit, false, 1, &err));
+        assert!(policy.should_retry(BrandApiEndpoint::Deposit, true, 1, &err));
+    }
+
+    #[test]
+    fn retries_rate_limited_non_idempotent_calls_with_key_only() {
+        let policy = BrandApiRetryPolicy::default();
+        let err = get_error(Some(429));
+
+        assert!(!policy.should_retry(BrandApiEndpoint::Deposit, false, 1, &err));
+        assert!(policy.should_retry(BrandApiEndpoint::Deposit, true, 1, &err));
+        assert!(!policy.should_retry(BrandApiEndpoint::Deposit, true, 3, &err));
+        assert!(policy.should_retry(BrandApiEndpoint::GetAccount, false, 1, &err));
+    }
+
+    #[test]
+    fn respects_max_attempts_and_statuses() {
+        let policy = BrandApiRetryPolicy::default();
+
+        assert!(policy.should_retry(BrandApiEndpoint::GetAccount, false, 2, &get_error(None)));
+        assert!(!policy.should_retry(BrandApiEndpoint::GetAccount, false, 3, &get_error(None)));
+        assert!(!policy.should_retry(BrandApiEndpoint::GetAccount, false, 1, &get_error(Some(400))));
+        assert!(!BrandApiRetryPolicy::no_retries().should_retry(
+            BrandApiEndpoint::GetAccount,
+            false,
+            1,
+            &get_error(Some(503))
+        ));
+    }
+
+    #[test]
+    fn grows_backoff_exponentially() {
+        let policy = BrandApiRetryPolicy {
+            jitter: false,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.get_backoff(1), Duration::from_millis(200));
+        assert_eq!(policy.get_backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.get_backoff(10), Duration::from_secs(5));
+
+        let policy = BrandApiRetryPolicy::default();
+        let backoff = policy.get_backoff(2);
+        assert!(backoff >= Duration::from_millis(200) && backoff <= Duration::from_millis(400));
+    }
+}
@@ -0,0 +1,85 @@
+//! Fixtures shared by the tests of BrandApiClient and the flows built on it.
+
+use crate::brand::api_client::{BrandApiClient, BrandApiConfig};
+use crate::brand::mock_server::MockBrandApi;
+use crate::brand::{
+    CreateAccountRequest, CreateUserRequest, OpenedPositionModel, OpenedPositionSide,
+};
+use crate::models::{AccountId, AccountType};
+use chrono::Utc;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub struct TestConfig;
+
+#[async_trait::async_trait]
+impl BrandApiConfig for TestConfig {
+    async fn get_api_url(&self) -> String {
+        "http://mock".to_string()
+    }
+
+    async fn get_api_key(&self) -> String {
+        "key".to_string()
+    }
+
+    async fn get_timeout(&self) -> Duration {
+        Duration::from_secs(1)
+    }
+}
+
+/// Client sending its requests to the mock api. The mock must accept the "key" api key.
+pub fn get_client(api: &Arc<MockBrandApi>) -> BrandApiClient<TestConfig> {
+    BrandApiClient::new(TestConfig).with_transport(api.get_transport())
+}
+
+/// Creates a user with a live USD account through the client.
+pub async fn create_account(client: &BrandApiClient<TestConfig>) -> AccountId {
+    let user = client
+        .create_user(
+            &CreateUserRequest {
+                email: "trader@example.com".to_string(),
+                password: "Qwerty!123".to_string(),
+                first_name: None,
+                last_name: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+    let account = client
+        .create_account(
+            &CreateAccountRequest {
+                user_id: user.user_id,
+                account_name: "test".to_string(),
+                account_type: AccountType::Live,
+                currency: "USD".to_string(),
+                group_id: None,
+            },
+            None,
+        )
+        .await
+        .unwrap();
+
+    account.account_id
+}
+
+/// One lot EURUSD buy position of the account.
+pub fn get_opened_position(position_id: &str, account_id: &AccountId) -> OpenedPositionModel {
+    OpenedPositionModel {
+        id: position_id.into(),
+        account_id: account_id.clone(),
+        lots: "1".to_string(),
+        lot_size: "100000".to_string(),
+        units: "100000".to_string(),
+        open_date_time: Utc::now(),
+        pnl: "0".parse().unwrap(),
+        swap: "0".parse().unwrap(),
+        sl_price: None,
+        tp_price: None,
+        open_price: "1.1".to_string(),
+        side: OpenedPositionSide::Buy,
+        instrument: "EURUSD".to_string(),
+        current_price: "1.1".to_string(),
+        commission: "0".parse().unwrap(),
+    }
+}
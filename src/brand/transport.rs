@@ -0,0 +1,127 @@
+use flurl::FlUrl;
+use http::Method;
+use std::time::Duration;
+
+/// Http request built by BrandApiClient.
+#[derive(Debug, Clone)]
+pub struct BrandApiTransportRequest {
+    pub method: Method,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+    pub timeout: Duration,
+}
+
+impl BrandApiTransportRequest {
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BrandApiTransportResponse {
+    pub status_code: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl BrandApiTransportResponse {
+    pub fn get_header(&self, name: &str) -> Option<&str> {
+        find_header(&self.headers, name)
+    }
+}
+
+/// Sends http requests for BrandApiClient. Implement it to replace the http stack,
+/// e.g. with an in-memory fake in tests. Err means no response was received.
+#[async_trait::async_trait]
+pub trait BrandApiTransport {
+    async fn send(
+        &self,
+        request: BrandApiTransportRequest,
+    ) -> Result<BrandApiTransportResponse, String>;
+}
+
+/// Default transport based on FlUrl.
+pub struct FlUrlBrandApiTransport;
+
+/// Response headers forwarded by FlUrlBrandApiTransport.
+const FORWARDED_RESPONSE_HEADERS: [&str; 1] = ["retry-after"];
+
+#[async_trait::async_trait]
+impl BrandApiTransport for FlUrlBrandApiTransport {
+    async fn send(
+        &self,
+        request: BrandApiTransportRequest,
+    ) -> Result<BrandApiTransportResponse, String> {
+        let mut flurl = FlUrl::new(&request.url).set_timeout(request.timeout);
+
+        for (name, value) in &request.headers {
+            flurl = flurl.with_header(name, value.as_str());
+        }
+
+        let http_method = request.method;
+        let body = request.body;
+
+        let result = if http_method == Method::GET {
+            flurl.get().await
+        } else if http_method == Method::POST {
+            flurl.post(body).await
+        } else if http_method == Method::PUT {
+            flurl.put(body).await
+        } else if http_method == Method::PATCH {
+            flurl.patch(body).await
+        } else if http_method == Method::DELETE {
+            flurl.delete().await
+        } else {
+            return Err(format!("Http method {} is not supported", http_method));
+        };
+
+        let mut response = result.map_err(|err| format!("{:?}", err))?;
+        let status_code = response.get_status_code();
+        let headers = FORWARDED_RESPONSE_HEADERS
+            .iter()
+            .filter_map(|name| {
+                response
+                    .get_header(name)
+                    .map(|value| (name.to_string(), value.to_string()))
+            })
+            .collect();
+        let body = response
+            .receive_body()
+            .await
+            .map_err(|err| format!("FlUrl failed to receive_body: {:?}", err))?;
+
+        Ok(BrandApiTransportResponse {
+            status_code,
+            headers,
+            body,
+        })
+    }
+}
+
+fn find_header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn rejects_unsupported_method() {
+        let request = BrandApiTransportRequest {
+            method: Method::OPTIONS,
+            url: "http://127.0.0.1:9/trade/accounts".to_string(),
+            headers: vec![],
+            body: None,
+            timeout: Duration::from_secs(1),
+        };
+
+        let result = FlUrlBrandApiTransport.send(request).await;
+
+        assert_eq!(result.unwrap_err(), "Http method OPTIONS is not supported");
+    }
+}
@@ -0,0 +1,407 @@
+//! Pre-flight checks of documented Brand API constraints. BrandApiClient validates every
+//! request before sending it, so violations fail fast with a typed error instead of a 400.
+
+use crate::brand::errors::BrandApiValidationError;
+use crate::brand::report_range::MAX_REPORT_WINDOW;
+use crate::brand::{
+    AccountOperationRequest, CancelOrderRequest, CheckEmailRequest, CloseAccountPositionsRequest,
+    CreateAccountRequest, CreateUserRequest, CreditAccountRequest, GetAccountRequest,
+    GetAccountsReportRequest, GetAssetsRequest, GetClosedPositionsReportRequest,
+    GetClosedTradesReportRequest, GetCursorReportRequest, GetGroupsRequest, GetInstrumentsRequest,
+    GetOpenedPositionsRequest, GetOrdersRequest, GetTradesReportRequest,
+    MonthlyActiveAccountsRequest, SetAccountGroupRequest, SetUserPasswordRequest,
+    UpdateAccountStatusRequest,
+};
+use crate::models::{AccountId, Amount};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::cmp::Ordering;
+
+/// Active ISO 4217 currency codes, including funds and precious metals.
+const ISO_4217_CODES: &[&str] = &[
+    "AED", "AFN", "ALL", "AMD", "ANG", "AOA", "ARS", "AUD", "AWG", "AZN", "BAM", "BBD", "BDT",
+    "BGN", "BHD", "BIF", "BMD", "BND", "BOB", "BOV", "BRL", "BSD", "BTN", "BWP", "BYN", "BZD",
+    "CAD", "CDF", "CHE", "CHF", "CHW", "CLF", "CLP", "CNY", "COP", "COU", "CRC", "CUP", "CVE",
+    "CZK", "DJF", "DKK", "DOP", "DZD", "EGP", "ERN", "ETB", "EUR", "FJD", "FKP", "GBP", "GEL",
+    "GHS", "GIP", "GMD", "GNF", "GTQ", "GYD", "HKD", "HNL", "HTG", "HUF", "IDR", "ILS", "INR",
+    "IQD", "IRR", "ISK", "JMD", "JOD", "JPY", "KES", "KGS", "KHR", "KMF", "KPW", "KRW", "KWD",
+    "KYD", "KZT", "LAK", "LBP", "LKR", "LRD", "LSL", "LYD", "MAD", "MDL", "MGA", "MKD", "MMK",
+    "MNT", "MOP", "MRU", "MUR", "MVR", "MWK", "MXN", "MXV", "MYR", "MZN", "NAD", "NGN", "NIO",
+    "NOK", "NPR", "NZD", "OMR", "PAB", "PEN", "PGK", "PHP", "PKR", "PLN", "PYG", "QAR", "RON",
+    "RSD", "RUB", "RWF", "SAR", "SBD", "SCR", "SDG", "SEK", "SGD", "SHP", "SLE", "SOS", "SRD",
+    "SSP", "STN", "SVC", "SYP", "SZL", "THB", "TJS", "TMT", "TND", "TOP", "TRY", "TTD", "TWD",
+    "TZS", "UAH", "UGX", "USD", "USN", "UYI", "UYU", "UYW", "UZS", "VED", "VES", "VND", "VUV",
+    "WST", "XAF", "XAG", "XAU", "XBA", "XBB", "XBC", "XBD", "XCD", "XCG", "XDR", "XOF", "XPD",
+    "XPF", "XPT", "XSU", "XUA", "YER", "ZAR", "ZMW", "ZWG",
+];
+
+/// Request which can be checked before sending. Requests without documented
+/// constraints use the default implementation.
+pub trait ValidateRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        Ok(())
+    }
+}
+
+/// Used for endpoints without a request body.
+impl ValidateRequest for String {}
+
+impl ValidateRequest for CreateUserRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_email(&self.email)
+    }
+}
+
+impl ValidateRequest for CheckEmailRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_email(&self.email)
+    }
+}
+
+impl ValidateRequest for SetUserPasswordRequest {}
+
+impl ValidateRequest for GetAccountRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_id(&self.account_id)
+    }
+}
+
+impl ValidateRequest for CreateAccountRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_currency(&self.currency)
+    }
+}
+
+impl ValidateRequest for UpdateAccountStatusRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_id(&self.account_id)
+    }
+}
+
+impl ValidateRequest for SetAccountGroupRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_id(&self.account_id)
+    }
+}
+
+impl ValidateRequest for CloseAccountPositionsRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_id(&self.account_id)
+    }
+}
+
+impl ValidateRequest for CreditAccountRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_id(&self.account_id)?;
+
+        match compare_to_zero(&self.amount) {
+            Ordering::Equal => Err(BrandApiValidationError::ZeroAmount),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ValidateRequest for AccountOperationRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_id(&self.account_id)?;
+
+        match compare_to_zero(&self.amount) {
+            Ordering::Greater => Ok(()),
+            _ => Err(BrandApiValidationError::NonPositiveAmount(
+                self.amount.to_string(),
+            )),
+        }
+    }
+}
+
+impl ValidateRequest for GetInstrumentsRequest {}
+
+impl ValidateRequest for GetAssetsRequest {}
+
+impl ValidateRequest for GetGroupsRequest {}
+
+impl ValidateRequest for GetOpenedPositionsRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_optional_account_id(self.account_id.as_ref())
+    }
+}
+
+impl ValidateRequest for GetClosedTradesReportRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_ids(self.account_ids.as_deref())?;
+        validate_date_range(
+            self.start_date_time,
+            self.end_date_time,
+            Some(MAX_REPORT_WINDOW),
+        )
+    }
+}
+
+impl ValidateRequest for GetClosedPositionsReportRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_ids(self.account_ids.as_deref())?;
+        validate_date_range(
+            self.start_date_time,
+            self.end_date_time,
+            Some(MAX_REPORT_WINDOW),
+        )
+    }
+}
+
+impl ValidateRequest for GetTradesReportRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_ids(self.account_ids.as_deref())?;
+
+        match (self.start_date_time, self.end_date_time) {
+            (Some(start), Some(end)) => validate_date_range(start, end, None),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ValidateRequest for GetCursorReportRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_optional_account_id(self.account_id.as_ref())?;
+
+        match (self.start_date_time, self.end_date_time) {
+            (Some(start), Some(end)) => validate_date_range(start, end, None),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ValidateRequest for GetAccountsReportRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_account_ids(self.account_ids.as_deref())
+    }
+}
+
+impl ValidateRequest for CancelOrderRequest {}
+
+impl ValidateRequest for GetOrdersRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_optional_account_id(self.account_id.as_ref())
+    }
+}
+
+impl ValidateRequest for MonthlyActiveAccountsRequest {
+    fn validate(&self) -> Result<(), BrandApiValidationError> {
+        validate_month(&self.for_month)
+    }
+}
+
+fn validate_account_id(account_id: &AccountId) -> Result<(), BrandApiValidationError> {
+    if account_id.as_str().trim().is_empty() {
+        return Err(BrandApiValidationError::EmptyAccountId);
+    }
+
+    Ok(())
+}
+
+fn validate_optional_account_id(
+    account_id: Option<&AccountId>,
+) -> Result<(), BrandApiValidationError> {
+    account_id.map_or(Ok(()), validate_account_id)
+}
+
+fn validate_account_ids(account_ids: Option<&[AccountId]>) -> Result<(), BrandApiValidationError> {
+    account_ids
+        .unwrap_or_default()
+        .iter()
+        .try_for_each(validate_account_id)
+}
+
+/// Checks that start is not after end and, if `max` is set, that the range fits into it.
+pub fn validate_date_range(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    max: Option<TimeDelta>,
+) -> Result<(), BrandApiValidationError> {
+    if start > end {
+        return Err(BrandApiValidationError::InvalidDateRange { start, end });
+    }
+
+    match max {
+        Some(max) if end - start > max => {
+            Err(BrandApiValidationError::DateRangeTooLong { start, end, max })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks the YYYY-MM format with a month in 1..=12.
+pub fn validate_month(month: &str) -> Result<(), BrandApiValidationError> {
+    let is_valid = match month.split_once('-') {
+        Some((year, month)) => {
+            year.len() == 4
+                && year.bytes().all(|byte| byte.is_ascii_digit())
+                && month.len() == 2
+                && month
+                    .parse::<u32>()
+                    .is_ok_and(|month| (1..=12).contains(&month))
+        }
+        None => false,
+    };
+
+    if is_valid {
+        return Ok(());
+    }
+
+    Err(BrandApiValidationError::InvalidMonth(month.to_string()))
+}
+
+pub fn validate_currency(currency: &str) -> Result<(), BrandApiValidationError> {
+    if ISO_4217_CODES.contains(&currency) {
+        return Ok(());
+    }
+
+    Err(BrandApiValidationError::InvalidCurrency(
+        currency.to_string(),
+    ))
+}
+
+/// Structural check: a single @, non-empty local part and a dotted domain without empty labels.
+pub fn validate_email(email: &str) -> Result<(), BrandApiValidationError> {
+    let is_valid = match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.contains('@')
+                && !email.chars().any(char::is_whitespace)
+                && domain.contains('.')
+                && domain.split('.').all(|label| !label.is_empty())
+        }
+        None => false,
+    };
+
+    if is_valid {
+        return Ok(());
+    }
+
+    Err(BrandApiValidationError::InvalidEmail(email.to_string()))
+}
+
+fn compare_to_zero(amount: &Amount) -> Ordering {
+    if amount.is_zero() {
+        Ordering::Equal
+    } else if amount.is_negative() {
+        Ordering::Less
+    } else {
+        Ordering::Greater
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AccountType;
+
+    fn get_operation(account_id: &str, amount: &str) -> AccountOperationRequest {
+        AccountOperationRequest {
+            account_id: account_id.into(),
+            amount: amount.parse().unwrap(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn validates_operation_amount() {
+        assert!(get_operation("L#1", "10.5").validate().is_ok());
+        assert_eq!(
+            get_operation("L#1", "0").validate(),
+            Err(BrandApiValidationError::NonPositiveAmount("0".to_string()))
+        );
+        assert!(matches!(
+            get_operation("L#1", "-1").validate(),
+            Err(BrandApiValidationError::NonPositiveAmount(_))
+        ));
+        assert_eq!(
+            get_operation(" ", "1").validate(),
+            Err(BrandApiValidationError::EmptyAccountId)
+        );
+    }
+
+    #[test]
+    fn validates_credit_amount() {
+        let mut request = CreditAccountRequest {
+            account_id: "L#1".into(),
+            amount: "-5".parse().unwrap(),
+            note: None,
+        };
+        assert!(request.validate().is_ok());
+
+        request.amount = "0.00".parse().unwrap();
+        assert_eq!(request.validate(), Err(BrandApiValidationError::ZeroAmount));
+    }
+
+    #[test]
+    fn validates_date_range() {
+        let start = "2024-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap();
+        let mut request = GetClosedTradesReportRequest {
+            account_ids: None,
+            account_type: AccountType::Live,
+            start_date_time: start,
+            end_date_time: start + TimeDelta::days(31),
+        };
+        assert!(request.validate().is_ok());
+
+        request.end_date_time = start + TimeDelta::days(32);
+        assert!(matches!(
+            request.validate(),
+            Err(BrandApiValidationError::DateRangeTooLong { .. })
+        ));
+
+        request.end_date_time = start - TimeDelta::seconds(1);
+        assert!(matches!(
+            request.validate(),
+            Err(BrandApiValidationError::InvalidDateRange { .. })
+        ));
+    }
+
+    #[test]
+    fn validates_currency() {
+        assert!(validate_currency("USD").is_ok());
+        assert!(validate_currency("usd").is_err());
+        assert!(validate_currency("ABC").is_err());
+        assert!(validate_currency("").is_err());
+    }
+
+    #[test]
+    fn validates_email() {
+        assert!(validate_email("trader@example.com").is_ok());
+        assert!(validate_email("first.last+tag@mail.example.co.uk").is_ok());
+
+        for email in [
+            "",
+            "trader",
+            "@example.com",
+            "trader@",
+            "trader@example",
+            "a@b@c.com",
+            "tra der@example.com",
+            "trader@example..com",
+        ] {
+            assert!(validate_email(email).is_err(), "{}", email);
+        }
+    }
+
+    #[test]
+    fn validates_month() {
+        assert!(validate_month("2026-09").is_ok());
+
+        for month in [
+            "",
+            "2026",
+            "2026-9",
+            "2026-00",
+            "2026-13",
+            "26-09",
+            "2026-09-01",
+        ] {
+            assert_eq!(
+                validate_month(month),
+                Err(BrandApiValidationError::InvalidMonth(month.to_string())),
+                "{}",
+                month
+            );
+        }
+    }
+}
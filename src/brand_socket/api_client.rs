@@ -1,5 +1,13 @@
 use crate::brand_socket::callback::{BrandSocketApiEventHandler, BrandSocketApiInner};
+use crate::brand_socket::channel::{
+    BrandSocketChannelConfig, BrandSocketChannelHandler, BrandSocketChannelMetrics,
+    BrandSocketEventQueue, BrandSocketEventReceiver,
+};
+use crate::brand_socket::session::BrandSocketSession;
+use crate::brand_socket::supervisor::{BrandSocketConnectionState, BrandSocketReconnectPolicy};
+use crate::brand_socket::watchdog::{BrandSocketHealth, BrandSocketWatchdogConfig};
 use crate::models::AccountType;
+use chrono::Utc;
 use my_socket_io_client::{
     my_web_socket_client, MySocketIoClient, SocketIoClientSettings, WsClientSettings,
 };
@@ -7,6 +15,8 @@ use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_extensions::Logger;
 use std::sync::Arc;
 use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 
 #[async_trait::async_trait]
 pub trait BrandSocketApiConfig {
@@ -16,10 +26,11 @@ pub trait BrandSocketApiConfig {
 }
 
 pub struct BrandSocketApiClient {
-    config_wrapper: Arc<BrandSocketApiConfigWrapper>,
-    socket_io_client: std::sync::Mutex<Option<MySocketIoClient>>,
-    inner: Arc<BrandSocketApiInner>,
-    logger: Arc<dyn Logger + Send + Sync + 'static>,
+    connector: Arc<BrandSocketConnector>,
+    reconnect_policy: Option<BrandSocketReconnectPolicy>,
+    watchdog_config: Option<BrandSocketWatchdogConfig>,
+    events: Option<Arc<BrandSocketEventQueue>>,
+    background_tasks: std::sync::Mutex<Vec<JoinHandle<()>>>,
 }
 
 impl BrandSocketApiClient {
@@ -31,29 +42,173 @@ impl BrandSocketApiClient {
         let config_wrapper = Arc::new(BrandSocketApiConfigWrapper::new(config));
 
         Self {
-            inner: Arc::new(BrandSocketApiInner::new(handler, Arc::clone(&logger))),
-            config_wrapper,
-            socket_io_client: Default::default(),
-            logger,
+            connector: Arc::new(BrandSocketConnector {
+                inner: Arc::new(BrandSocketApiInner::new(handler, Arc::clone(&logger))),
+                config_wrapper,
+                socket_io_client: Default::default(),
+                logger,
+            }),
+            reconnect_policy: None,
+            watchdog_config: None,
+            events: None,
+            background_tasks: Default::default(),
         }
     }
 
-    pub async fn disconnect(&self) -> Result<(), String> {
-        self.inner.disconnect().await;
-        let socket_io_client = self.socket_io_client.lock().unwrap().take();
+    /// Queues the events with their context and the connection notifications for the returned
+    /// receiver instead of passing them to a handler, so that a slow consumer does not stall
+    /// the socket reader unless the overflow policy is Block.
+    pub fn new_with_channel(
+        config: Arc<dyn BrandSocketApiConfig + Send + Sync>,
+        logger: Arc<dyn Logger + Send + Sync + 'static>,
+        channel_config: BrandSocketChannelConfig,
+    ) -> (Self, BrandSocketEventReceiver) {
+        let events = Arc::new(BrandSocketEventQueue::new(channel_config));
+        let handler = Arc::new(BrandSocketChannelHandler::new(Arc::clone(&events)));
+        let mut client = Self::new(handler, config, logger);
+        client.events = Some(Arc::clone(&events));
 
-        if let Some(socket_io_client) = socket_io_client {
-            socket_io_client.stop();
-        }
+        (client, BrandSocketEventReceiver::new(events))
+    }
+
+    /// Keeps the connection alive after connect: when it is lost or does not receive SyncEnd
+    /// in time, the client reconnects with backoff until the policy gives up.
+    pub fn with_reconnect_policy(mut self, reconnect_policy: BrandSocketReconnectPolicy) -> Self {
+        self.reconnect_policy = Some(reconnect_policy);
+        self
+    }
+
+    /// Checks the connection for events after connect. When none arrive for the stale timeout,
+    /// calls BrandSocketApiEventHandler::on_stream_stale and optionally reconnects.
+    pub fn with_watchdog(mut self, watchdog_config: BrandSocketWatchdogConfig) -> Self {
+        self.watchdog_config = Some(watchdog_config);
+        self
+    }
+
+    /// Closes the connection. Stops reconnecting and the watchdog if they are set.
+    pub async fn disconnect(&self) -> Result<(), String> {
+        self.stop_background_tasks();
+        self.connector.stop().await;
+        self.connector
+            .inner
+            .set_connection_state(BrandSocketConnectionState::Disconnected)
+            .await;
 
         Ok(())
     }
 
     pub async fn is_connected(&self) -> bool {
-        self.socket_io_client.lock().unwrap().is_some() && self.inner.is_connected().await
+        self.connector.socket_io_client.lock().unwrap().is_some()
+            && self.connector.inner.is_connected().await
     }
 
     pub async fn connect(&self) -> Result<(), String> {
+        self.stop_background_tasks();
+        self.connector.start().await;
+        let mut background_tasks = self.background_tasks.lock().unwrap();
+
+        if let Some(reconnect_policy) = &self.reconnect_policy {
+            background_tasks.push(tokio::spawn(supervise(
+                Arc::clone(&self.connector),
+                reconnect_policy.clone(),
+            )));
+        }
+
+        if let Some(watchdog_config) = &self.watchdog_config {
+            background_tasks.push(tokio::spawn(watch_stream(
+                Arc::clone(&self.connector),
+                watchdog_config.clone(),
+                self.reconnect_policy.is_some(),
+            )));
+        }
+
+        if let Some(events) = &self.events {
+            background_tasks.push(tokio::spawn(watch_overflow(
+                Arc::clone(&self.connector),
+                Arc::clone(events),
+            )));
+        }
+
+        Ok(())
+    }
+
+    pub async fn wait_until_sync_ended(&self, timeout: Duration) -> Result<(), String> {
+        self.connector.inner.wait_until_sync_ended(timeout).await
+    }
+
+    pub fn get_last_event_timestamp(&self) -> Option<DateTimeAsMicroseconds> {
+        self.connector.inner.get_last_event_timestamp()
+    }
+
+    /// Sync progress of the current connection, None while disconnected.
+    pub fn get_session(&self) -> Option<BrandSocketSession> {
+        self.connector.inner.get_session()
+    }
+
+    pub fn get_connection_state(&self) -> BrandSocketConnectionState {
+        self.connector.inner.get_connection_state()
+    }
+
+    /// Receives every connection state change, e.g. to wait for Synced after a reconnection.
+    pub fn subscribe_connection_state(&self) -> watch::Receiver<BrandSocketConnectionState> {
+        self.connector.inner.subscribe_connection_state()
+    }
+
+    /// Liveness of the connection, e.g. for a health check endpoint.
+    pub fn get_health(&self) -> BrandSocketHealth {
+        let inner = &self.connector.inner;
+        let now = Utc::now();
+        let trading_hours = self
+            .watchdog_config
+            .as_ref()
+            .and_then(|watchdog_config| watchdog_config.trading_hours.as_ref());
+        let idle_for = inner.get_idle_for(now, trading_hours);
+        let is_stale = match (&self.watchdog_config, idle_for) {
+            (Some(watchdog_config), Some(idle_for)) => watchdog_config.is_stale(idle_for, now),
+            _ => false,
+        };
+
+        BrandSocketHealth {
+            connection_state: inner.get_connection_state(),
+            session: inner.get_session(),
+            last_event_at: inner.get_last_event_at(),
+            idle_for,
+            is_stale,
+        }
+    }
+
+    /// Queue metrics of a client created with new_with_channel, None otherwise.
+    pub fn get_channel_metrics(&self) -> Option<BrandSocketChannelMetrics> {
+        self.events.as_ref().map(|events| events.get_metrics())
+    }
+
+    fn stop_background_tasks(&self) {
+        for background_task in self.background_tasks.lock().unwrap().drain(..) {
+            background_task.abort();
+        }
+    }
+}
+
+impl Drop for BrandSocketApiClient {
+    fn drop(&mut self) {
+        self.stop_background_tasks();
+
+        if let Some(events) = &self.events {
+            events.close();
+        }
+    }
+}
+
+/// Starts and stops socket io clients. Shared by BrandSocketApiClient, its supervisor and watchdog.
+struct BrandSocketConnector {
+    config_wrapper: Arc<BrandSocketApiConfigWrapper>,
+    socket_io_client: std::sync::Mutex<Option<MySocketIoClient>>,
+    inner: Arc<BrandSocketApiInner>,
+    logger: Arc<dyn Logger + Send + Sync + 'static>,
+}
+
+impl BrandSocketConnector {
+    async fn start(&self) {
         my_web_socket_client::my_tls::install_default_crypto_providers();
         let is_debug = std::env::var("DEBUG").is_ok();
 
@@ -61,6 +216,10 @@ impl BrandSocketApiClient {
             println!("BrandSocketApiClient: Debug payloads are enabled");
         }
 
+        self.inner.reset_sync();
+        self.inner
+            .set_connection_state(BrandSocketConnectionState::Connecting)
+            .await;
         let socket_io_client = MySocketIoClient::new(
             "trade-locker-brand-socket",
             self.config_wrapper.clone(),
@@ -73,20 +232,148 @@ impl BrandSocketApiClient {
             .register_subscriber(self.inner.clone())
             .await;
         socket_io_client.start();
-        self.socket_io_client
+        let prev_socket_io_client = self
+            .socket_io_client
             .lock()
             .unwrap()
             .replace(socket_io_client);
 
-        Ok(())
+        if let Some(prev_socket_io_client) = prev_socket_io_client {
+            prev_socket_io_client.stop();
+        }
     }
 
-    pub async fn wait_until_sync_ended(&self, timeout: Duration) -> Result<(), String> {
-        self.inner.wait_until_sync_ended(timeout).await
+    async fn stop(&self) {
+        self.inner.disconnect().await;
+        self.inner.reset_sync();
+        let socket_io_client = self.socket_io_client.lock().unwrap().take();
+
+        if let Some(socket_io_client) = socket_io_client {
+            socket_io_client.stop();
+        }
+    }
+}
+
+/// Waits for SyncEnd after every (re)connection and for the loss of a synced connection,
+/// then reconnects with backoff. Runs until aborted or the policy gives up.
+async fn supervise(connector: Arc<BrandSocketConnector>, policy: BrandSocketReconnectPolicy) {
+    let inner = &connector.inner;
+    let mut connection_state = inner.subscribe_connection_state();
+    let mut attempt = 0;
+
+    loop {
+        match inner.wait_until_sync_ended(policy.sync_timeout).await {
+            Ok(()) => {
+                attempt = 0;
+                _ = connection_state
+                    .wait_for(|state| *state == BrandSocketConnectionState::Disconnected)
+                    .await;
+            }
+            Err(err) => connector.logger.write_warning(
+                "BrandSocketApiClient.supervise".to_string(),
+                format!("Connection is not synced: {}", err),
+                None,
+            ),
+        }
+
+        attempt += 1;
+
+        if !policy.should_reconnect(attempt) {
+            connector.stop().await;
+            inner
+                .set_connection_state(BrandSocketConnectionState::GaveUp {
+                    attempts: attempt - 1,
+                })
+                .await;
+            return;
+        }
+
+        let delay = policy.get_backoff(attempt);
+        inner
+            .set_connection_state(BrandSocketConnectionState::Reconnecting { attempt, delay })
+            .await;
+        tokio::time::sleep(delay).await;
+        connector.stop().await;
+        connector.start().await;
     }
+}
 
-    pub fn get_last_event_timestamp(&self) -> Option<DateTimeAsMicroseconds> {
-        self.inner.get_last_event_timestamp()
+/// Reports a connection without events to the handler once per stale period and optionally
+/// closes it. Without a supervisor it connects again itself, backing off like the default
+/// reconnect policy until a live event is received. Runs until aborted.
+async fn watch_stream(
+    connector: Arc<BrandSocketConnector>,
+    config: BrandSocketWatchdogConfig,
+    is_supervised: bool,
+) {
+    let inner = &connector.inner;
+    let backoff = BrandSocketReconnectPolicy::default();
+    let mut is_reported = false;
+    let mut attempt = 0;
+
+    loop {
+        tokio::time::sleep(config.check_interval).await;
+        let now = Utc::now();
+        let idle_for = inner.get_idle_for(now, config.trading_hours.as_ref());
+
+        if inner.has_live_events() {
+            attempt = 0;
+        }
+
+        let Some(idle_for) = idle_for.filter(|idle_for| config.is_stale(*idle_for, now)) else {
+            is_reported = false;
+            continue;
+        };
+
+        if is_reported {
+            continue;
+        }
+
+        is_reported = true;
+        connector.logger.write_warning(
+            "BrandSocketApiClient.watch_stream".to_string(),
+            format!("No events for {:?}", idle_for),
+            None,
+        );
+        inner.report_stale_stream(idle_for).await;
+
+        if config.force_reconnect {
+            connector.stop().await;
+            inner
+                .set_connection_state(BrandSocketConnectionState::Disconnected)
+                .await;
+
+            if !is_supervised {
+                attempt += 1;
+                let delay = backoff.get_backoff(attempt);
+                inner
+                    .set_connection_state(BrandSocketConnectionState::Reconnecting {
+                        attempt,
+                        delay,
+                    })
+                    .await;
+                tokio::time::sleep(delay).await;
+                connector.start().await;
+            }
+        }
+    }
+}
+
+/// Closes the connection whenever the event queue overflows with
+/// BrandSocketOverflowPolicy::Disconnect. A supervisor reconnects it. Runs until aborted.
+async fn watch_overflow(connector: Arc<BrandSocketConnector>, events: Arc<BrandSocketEventQueue>) {
+    loop {
+        events.wait_for_overflow().await;
+        connector.logger.write_warning(
+            "BrandSocketApiClient.watch_overflow".to_string(),
+            format!("Event queue overflow: {:?}", events.get_metrics()),
+            None,
+        );
+        connector.stop().await;
+        connector
+            .inner
+            .set_connection_state(BrandSocketConnectionState::Disconnected)
+            .await;
     }
 }
 
@@ -1,26 +1,48 @@
 use super::models::*;
+use super::session::{BrandSocketEventContext, BrandSocketEventPhase, BrandSocketSession};
+use super::supervisor::BrandSocketConnectionState;
+use super::watchdog::BrandSocketTradingHours;
+use chrono::{DateTime, Utc};
 use my_socket_io_client::{SocketIoCallbacks, SocketIoConnection, SocketIoEventSubscriberCallback};
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 use rust_extensions::Logger;
 use std::sync::atomic::Ordering::Relaxed;
-use std::sync::atomic::{AtomicBool, AtomicI64};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicI64, AtomicU64};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
-use tokio::sync::RwLock;
+use tokio::sync::{watch, RwLock};
 
 #[async_trait::async_trait]
 pub trait BrandSocketApiEventHandler {
     async fn on_event(&self, event: BrandSocketEvent);
+    /// Same as on_event but tells whether the event is a part of the snapshot replayed after
+    /// connecting or a live update. Calls on_event unless overridden.
+    async fn on_event_with_context(
+        &self,
+        event: BrandSocketEvent,
+        _context: BrandSocketEventContext,
+    ) {
+        self.on_event(event).await
+    }
     async fn on_connected(&self);
     async fn on_disconnected(&self);
+    /// Called on every change of the connection state, including reconnections.
+    async fn on_connection_state_changed(&self, _state: BrandSocketConnectionState) {}
+    /// Called with every payload that is not a supported event. The connection stays open.
+    async fn on_undecodable_payload(&self, _payload: String, _err: BrandSocketEventDeserializeErr) {
+    }
+    /// Called by the watchdog once the connection received no events for `idle_for`.
+    async fn on_stream_stale(&self, _idle_for: Duration) {}
 }
 
 pub struct BrandSocketApiInner {
     handler: Arc<dyn BrandSocketApiEventHandler + Send + Sync + 'static>,
     connection: RwLock<Option<Arc<SocketIoConnection>>>,
     logger: Arc<dyn Logger + Send + Sync + 'static>,
-    sync_ended: AtomicBool,
+    session: Mutex<Option<BrandSocketSession>>,
+    last_session_id: AtomicU64,
     last_event_timestamp: AtomicI64,
+    connection_state: watch::Sender<BrandSocketConnectionState>,
 }
 
 impl BrandSocketApiInner {
@@ -32,8 +54,10 @@ impl BrandSocketApiInner {
             handler,
             connection: Default::default(),
             logger,
-            sync_ended: AtomicBool::new(false),
+            session: Default::default(),
+            last_session_id: Default::default(),
             last_event_timestamp: Default::default(),
+            connection_state: watch::channel(BrandSocketConnectionState::Disconnected).0,
         }
     }
 
@@ -45,7 +69,7 @@ impl BrandSocketApiInner {
         let instant = Instant::now();
 
         loop {
-            if self.is_connected().await && self.sync_ended.load(Relaxed) {
+            if self.is_connected().await && self.get_session().is_some_and(|s| s.is_synced()) {
                 return Ok(());
             }
 
@@ -65,6 +89,74 @@ impl BrandSocketApiInner {
         }
     }
 
+    /// Forgets the session of the previous connection, so that wait_until_sync_ended
+    /// waits for the snapshot of the next one.
+    pub fn reset_sync(&self) {
+        self.session.lock().unwrap().take();
+    }
+
+    /// Session of the current connection, None while disconnected.
+    pub fn get_session(&self) -> Option<BrandSocketSession> {
+        self.session.lock().unwrap().clone()
+    }
+
+    fn start_session(&self) {
+        self.session.lock().unwrap().replace(self.new_session());
+    }
+
+    fn new_session(&self) -> BrandSocketSession {
+        BrandSocketSession::new(self.last_session_id.fetch_add(1, Relaxed) + 1)
+    }
+
+    /// Counts the event in the current session. Returns its context and whether it ended the sync.
+    fn track_event(&self, is_sync_end: bool) -> (BrandSocketEventContext, bool) {
+        let mut session = self.session.lock().unwrap();
+        // events are not expected before on_connect, but must still belong to a session
+        let session = session.get_or_insert_with(|| self.new_session());
+        let mut context = BrandSocketEventContext {
+            session_id: session.id,
+            phase: BrandSocketEventPhase::Snapshot,
+        };
+
+        if session.is_synced() {
+            context.phase = BrandSocketEventPhase::Live;
+            return (context, false);
+        }
+
+        if is_sync_end {
+            session.sync_ended_at = Some(Utc::now());
+            return (context, true);
+        }
+
+        session.snapshot_events_count += 1;
+
+        (context, false)
+    }
+
+    pub fn get_connection_state(&self) -> BrandSocketConnectionState {
+        self.connection_state.borrow().clone()
+    }
+
+    pub fn subscribe_connection_state(&self) -> watch::Receiver<BrandSocketConnectionState> {
+        self.connection_state.subscribe()
+    }
+
+    /// Notifies the handler if the state changed.
+    pub async fn set_connection_state(&self, state: BrandSocketConnectionState) {
+        let is_changed = self.connection_state.send_if_modified(|current| {
+            if *current == state {
+                return false;
+            }
+
+            *current = state.clone();
+            true
+        });
+
+        if is_changed {
+            self.handler.on_connection_state_changed(state).await;
+        }
+    }
+
     pub fn get_last_event_timestamp(&self) -> Option<DateTimeAsMicroseconds> {
         let last_event_timestamp = self.last_event_timestamp.load(Relaxed);
 
@@ -74,6 +166,49 @@ impl BrandSocketApiInner {
             Some(DateTimeAsMicroseconds::from(last_event_timestamp))
         }
     }
+
+    pub fn get_last_event_at(&self) -> Option<DateTime<Utc>> {
+        let last_event_timestamp = self.last_event_timestamp.load(Relaxed);
+
+        if last_event_timestamp <= 0 {
+            None
+        } else {
+            DateTime::from_timestamp_micros(last_event_timestamp)
+        }
+    }
+
+    pub async fn report_stale_stream(&self, idle_for: Duration) {
+        self.handler.on_stream_stale(idle_for).await;
+    }
+
+    /// Time since the last event of the current session, its start or the opening of the trading
+    /// hours, whichever is later. None without a session.
+    pub fn get_idle_for(
+        &self,
+        now: DateTime<Utc>,
+        trading_hours: Option<&BrandSocketTradingHours>,
+    ) -> Option<Duration> {
+        let session = self.get_session()?;
+        let active_at = [
+            Some(session.sync_started_at),
+            self.get_last_event_at(),
+            trading_hours.map(|trading_hours| trading_hours.get_opened_at(now)),
+        ]
+        .into_iter()
+        .flatten()
+        .max()?;
+
+        Some((now - active_at).to_std().unwrap_or_default())
+    }
+
+    /// Whether an update was received after SyncEnd of the current session.
+    pub fn has_live_events(&self) -> bool {
+        let sync_ended_at = self.get_session().and_then(|session| session.sync_ended_at);
+
+        sync_ended_at
+            .zip(self.get_last_event_at())
+            .is_some_and(|(sync_ended_at, last_event_at)| last_event_at > sync_ended_at)
+    }
 }
 
 #[async_trait::async_trait]
@@ -85,11 +220,17 @@ impl SocketIoCallbacks for BrandSocketApiInner {
             prev_connection.disconnect().await;
         }
 
+        self.start_session();
+        self.set_connection_state(BrandSocketConnectionState::Connected)
+            .await;
         self.handler.on_connected().await;
     }
 
     async fn on_disconnect(&self, _connection: Arc<SocketIoConnection>) {
         _ = self.connection.write().await.take();
+        self.reset_sync();
+        self.set_connection_state(BrandSocketConnectionState::Disconnected)
+            .await;
         self.handler.on_disconnected().await;
     }
 }
@@ -98,37 +239,37 @@ impl SocketIoCallbacks for BrandSocketApiInner {
 impl SocketIoEventSubscriberCallback<BrandSocketEventDeserialized, ()> for BrandSocketApiInner {
     async fn on_event(&self, event: BrandSocketEventDeserialized) -> () {
         self.last_event_timestamp
-            .store(DateTimeAsMicroseconds::now().unix_microseconds, Relaxed);
+            .store(Utc::now().timestamp_micros(), Relaxed);
 
         match event.result {
             Ok(event) => {
-                match &event {
-                    BrandSocketEvent::AccountStatus(_) => {}
-                    BrandSocketEvent::Property(message) => {
-                        if message.name == "SyncEnd" {
-                            self.sync_ended.store(true, Relaxed);
-                        }
-                    }
-                    BrandSocketEvent::Position(_) => {}
-                    BrandSocketEvent::ClosePosition(_) => {}
-                    BrandSocketEvent::OpenOrder(_) => {}
-                    BrandSocketEvent::ConnectionError(_) => {}
+                let is_sync_end = match &event {
+                    BrandSocketEvent::AccountStatus(_) => false,
+                    BrandSocketEvent::Property(message) => message.name == "SyncEnd",
+                    BrandSocketEvent::Position(_) => false,
+                    BrandSocketEvent::ClosePosition(_) => false,
+                    BrandSocketEvent::OpenOrder(_) => false,
+                    BrandSocketEvent::ConnectionError(_) => false,
                 };
+                let (context, sync_ended) = self.track_event(is_sync_end);
+
+                if sync_ended {
+                    self.set_connection_state(BrandSocketConnectionState::Synced)
+                        .await;
+                }
 
-                self.handler.on_event(event).await;
+                self.handler.on_event_with_context(event, context).await;
             }
-            Err(err) => match err {
-                BrandSocketEventDeserializeErr::NotSupported(err) => self.logger.write_error(
+            Err(err) => {
+                self.logger.write_error(
                     "BrandSocketApiInner.on_event".to_string(),
-                    format!("Not supported event: {}", err),
+                    format!("{}. Payload: {}", err, event.payload),
                     None,
-                ),
-                BrandSocketEventDeserializeErr::Serde(err) => self.logger.write_error(
-                    "BrandSocketApiInner.on_event".to_string(),
-                    format!("Failed to deserialize: {}. Payload: {}", err, event.payload),
-                    None,
-                ),
-            },
+                );
+                self.handler
+                    .on_undecodable_payload(event.payload, err)
+                    .await;
+            }
         }
 
         ()
@@ -0,0 +1,432 @@
+//! Consumption of brand socket events through a bounded queue instead of a handler,
+//! see BrandSocketApiClient::new_with_channel.
+
+use crate::brand_socket::callback::BrandSocketApiEventHandler;
+use crate::brand_socket::models::{BrandSocketEvent, BrandSocketEventDeserializeErr};
+use crate::brand_socket::session::BrandSocketEventContext;
+use crate::brand_socket::supervisor::BrandSocketConnectionState;
+use futures_util::{stream, Stream};
+use std::collections::VecDeque;
+use std::sync::atomic::Ordering::Relaxed;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+
+/// Item of the queue, one per BrandSocketApiEventHandler callback. The notifications are queued
+/// in order with the events and count toward the capacity.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug)]
+pub enum BrandSocketChannelMessage {
+    Event {
+        event: BrandSocketEvent,
+        context: BrandSocketEventContext,
+    },
+    ConnectionStateChanged(BrandSocketConnectionState),
+    /// Payload that is not a supported event. The connection stays open.
+    UndecodablePayload {
+        payload: String,
+        err: BrandSocketEventDeserializeErr,
+    },
+    /// No events were received for `idle_for`, see BrandSocketApiClient::with_watchdog.
+    StreamStale(Duration),
+}
+
+impl BrandSocketChannelMessage {
+    /// Returns the event, None for notifications.
+    pub fn into_event(self) -> Option<BrandSocketEvent> {
+        match self {
+            BrandSocketChannelMessage::Event { event, .. } => Some(event),
+            _ => None,
+        }
+    }
+}
+
+/// What happens to a message received while the queue is full.
+#[derive(strum::Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrandSocketOverflowPolicy {
+    /// Waits for the consumer. The socket reader is stalled meanwhile.
+    Block,
+    /// Drops the oldest queued message to make room.
+    DropOldest,
+    /// Drops the message and closes the connection, so that the consumer gets a fresh snapshot
+    /// after the reconnection instead of a gap. Reconnects only with a reconnect policy.
+    Disconnect,
+}
+
+#[derive(Debug, Clone)]
+pub struct BrandSocketChannelConfig {
+    /// Max number of queued messages.
+    pub capacity: usize,
+    pub overflow_policy: BrandSocketOverflowPolicy,
+}
+
+impl Default for BrandSocketChannelConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            overflow_policy: BrandSocketOverflowPolicy::Block,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrandSocketChannelMetrics {
+    pub capacity: usize,
+    /// Messages waiting for the consumer.
+    pub depth: usize,
+    /// Highest depth since the channel was created.
+    pub max_depth: usize,
+    /// Messages received from the socket, including the dropped ones.
+    pub received_count: u64,
+    pub dropped_count: u64,
+}
+
+/// Queue shared by the socket handler, the receiver and the client.
+pub(crate) struct BrandSocketEventQueue {
+    config: BrandSocketChannelConfig,
+    messages: Mutex<VecDeque<BrandSocketChannelMessage>>,
+    enqueued: Notify,
+    dequeued: Notify,
+    overflowed: Notify,
+    is_closed: AtomicBool,
+    max_depth: AtomicUsize,
+    received_count: AtomicU64,
+    dropped_count: AtomicU64,
+}
+
+impl BrandSocketEventQueue {
+    pub fn new(mut config: BrandSocketChannelConfig) -> Self {
+        config.capacity = config.capacity.max(1);
+
+        Self {
+            messages: Mutex::new(VecDeque::with_capacity(config.capacity)),
+            config,
+            enqueued: Notify::new(),
+            dequeued: Notify::new(),
+            overflowed: Notify::new(),
+            is_closed: AtomicBool::new(false),
+            max_depth: AtomicUsize::new(0),
+            received_count: AtomicU64::new(0),
+            dropped_count: AtomicU64::new(0),
+        }
+    }
+
+    pub async fn push(&self, message: BrandSocketChannelMessage) {
+        self.received_count.fetch_add(1, Relaxed);
+        let mut message = Some(message);
+
+        loop {
+            {
+                let mut messages = self.messages.lock().unwrap();
+
+                if messages.len() < self.config.capacity {
+                    messages.extend(message.take());
+                    self.max_depth.fetch_max(messages.len(), Relaxed);
+                    self.enqueued.notify_one();
+                    return;
+                }
+
+                match self.config.overflow_policy {
+                    BrandSocketOverflowPolicy::Block => {}
+                    BrandSocketOverflowPolicy::DropOldest => {
+                        messages.pop_front();
+                        messages.extend(message.take());
+                        self.dropped_count.fetch_add(1, Relaxed);
+                        self.enqueued.notify_one();
+                        return;
+                    }
+                    BrandSocketOverflowPolicy::Disconnect => {
+                        self.dropped_count.fetch_add(1, Relaxed);
+                        self.overflowed.notify_one();
+                        return;
+                    }
+                }
+            }
+
+            if self.is_closed.load(Relaxed) {
+                self.dropped_count.fetch_add(1, Relaxed);
+                return;
+            }
+
+            self.dequeued.notified().await;
+        }
+    }
+
+    pub fn try_pop(&self) -> Option<BrandSocketChannelMessage> {
+        let message = self.messages.lock().unwrap().pop_front();
+
+        if message.is_some() {
+            self.dequeued.notify_one();
+        }
+
+        message
+    }
+
+    pub async fn pop(&self) -> Option<BrandSocketChannelMessage> {
+        loop {
+            if let Some(message) = self.try_pop() {
+                return Some(message);
+            }
+
+            if self.is_closed.load(Relaxed) {
+                return None;
+            }
+
+            self.enqueued.notified().await;
+        }
+    }
+
+    /// Resolves when a message was dropped by BrandSocketOverflowPolicy::Disconnect.
+    pub async fn wait_for_overflow(&self) {
+        self.overflowed.notified().await
+    }
+
+    /// Lets the receiver drain the queued messages and then end. Pending pushes are dropped.
+    pub fn close(&self) {
+        self.is_closed.store(true, Relaxed);
+        self.enqueued.notify_one();
+        self.dequeued.notify_one();
+    }
+
+    pub fn get_metrics(&self) -> BrandSocketChannelMetrics {
+        BrandSocketChannelMetrics {
+            capacity: self.config.capacity,
+            depth: self.messages.lock().unwrap().len(),
+            max_depth: self.max_depth.load(Relaxed),
+            received_count: self.received_count.load(Relaxed),
+            dropped_count: self.dropped_count.load(Relaxed),
+        }
+    }
+}
+
+/// Feeds the queue from the socket.
+pub(crate) struct BrandSocketChannelHandler {
+    queue: Arc<BrandSocketEventQueue>,
+}
+
+impl BrandSocketChannelHandler {
+    pub fn new(queue: Arc<BrandSocketEventQueue>) -> Self {
+        Self { queue }
+    }
+}
+
+#[async_trait::async_trait]
+impl BrandSocketApiEventHandler for BrandSocketChannelHandler {
+    /// Not called since on_event_with_context is overridden.
+    async fn on_event(&self, _event: BrandSocketEvent) {}
+
+    async fn on_event_with_context(
+        &self,
+        event: BrandSocketEvent,
+        context: BrandSocketEventContext,
+    ) {
+        let message = BrandSocketChannelMessage::Event { event, context };
+        self.queue.push(message).await;
+    }
+
+    /// Reported through on_connection_state_changed.
+    async fn on_connected(&self) {}
+
+    /// Reported through on_connection_state_changed.
+    async fn on_disconnected(&self) {}
+
+    async fn on_connection_state_changed(&self, state: BrandSocketConnectionState) {
+        let message = BrandSocketChannelMessage::ConnectionStateChanged(state);
+        self.queue.push(message).await;
+    }
+
+    async fn on_undecodable_payload(&self, payload: String, err: BrandSocketEventDeserializeErr) {
+        let message = BrandSocketChannelMessage::UndecodablePayload { payload, err };
+        self.queue.push(message).await;
+    }
+
+    async fn on_stream_stale(&self, idle_for: Duration) {
+        self.queue
+            .push(BrandSocketChannelMessage::StreamStale(idle_for))
+            .await;
+    }
+}
+
+/// Receives the messages of a client created with BrandSocketApiClient::new_with_channel.
+/// Ends after the client is dropped and the queued messages are received.
+pub struct BrandSocketEventReceiver {
+    queue: Arc<BrandSocketEventQueue>,
+}
+
+impl BrandSocketEventReceiver {
+    pub(crate) fn new(queue: Arc<BrandSocketEventQueue>) -> Self {
+        Self { queue }
+    }
+
+    pub async fn recv(&mut self) -> Option<BrandSocketChannelMessage> {
+        self.queue.pop().await
+    }
+
+    /// Returns a queued message without waiting.
+    pub fn try_recv(&mut self) -> Option<BrandSocketChannelMessage> {
+        self.queue.try_pop()
+    }
+
+    pub fn get_metrics(&self) -> BrandSocketChannelMetrics {
+        self.queue.get_metrics()
+    }
+
+    pub fn into_stream(self) -> impl Stream<Item = BrandSocketChannelMessage> {
+        stream::unfold(self, |mut receiver| async move {
+            let message = receiver.recv().await?;
+
+            Some((message, receiver))
+        })
+    }
+}
+
+impl Drop for BrandSocketEventReceiver {
+    fn drop(&mut self) {
+        // unblocks the socket reader waiting with BrandSocketOverflowPolicy::Block
+        self.queue.close();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand_socket::models::PropertyMessage;
+    use crate::brand_socket::session::BrandSocketEventPhase;
+    use futures_util::StreamExt;
+
+    const CONTEXT: BrandSocketEventContext = BrandSocketEventContext {
+        session_id: 1,
+        phase: BrandSocketEventPhase::Live,
+    };
+
+    fn property(name: &str) -> BrandSocketEvent {
+        BrandSocketEvent::Property(PropertyMessage {
+            name: name.to_string(),
+        })
+    }
+
+    fn property_message(name: &str) -> BrandSocketChannelMessage {
+        BrandSocketChannelMessage::Event {
+            event: property(name),
+            context: CONTEXT,
+        }
+    }
+
+    fn get_name(message: BrandSocketChannelMessage) -> String {
+        match message.into_event() {
+            Some(BrandSocketEvent::Property(message)) => message.name,
+            event => panic!("unexpected event {:?}", event),
+        }
+    }
+
+    fn new_queue(overflow_policy: BrandSocketOverflowPolicy) -> Arc<BrandSocketEventQueue> {
+        Arc::new(BrandSocketEventQueue::new(BrandSocketChannelConfig {
+            capacity: 2,
+            overflow_policy,
+        }))
+    }
+
+    #[tokio::test]
+    async fn drops_oldest_event_on_overflow() {
+        let queue = new_queue(BrandSocketOverflowPolicy::DropOldest);
+        let handler = BrandSocketChannelHandler::new(queue.clone());
+        let receiver = BrandSocketEventReceiver::new(queue.clone());
+
+        for name in ["1", "2", "3"] {
+            handler.on_event_with_context(property(name), CONTEXT).await;
+        }
+        queue.close();
+
+        assert_eq!(
+            receiver.get_metrics(),
+            BrandSocketChannelMetrics {
+                capacity: 2,
+                depth: 2,
+                max_depth: 2,
+                received_count: 3,
+                dropped_count: 1,
+            }
+        );
+        let names: Vec<_> = receiver.into_stream().map(get_name).collect().await;
+        assert_eq!(names, vec!["2", "3"]);
+    }
+
+    #[tokio::test]
+    async fn blocks_until_consumed() {
+        let queue = new_queue(BrandSocketOverflowPolicy::Block);
+        let mut receiver = BrandSocketEventReceiver::new(queue.clone());
+        queue.push(property_message("1")).await;
+        queue.push(property_message("2")).await;
+
+        let push = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.push(property_message("3")).await }
+        });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert!(!push.is_finished());
+
+        assert_eq!(get_name(receiver.recv().await.unwrap()), "1");
+        push.await.unwrap();
+        assert_eq!(receiver.get_metrics().depth, 2);
+        assert_eq!(receiver.get_metrics().dropped_count, 0);
+    }
+
+    #[tokio::test]
+    async fn signals_overflow_with_disconnect_policy() {
+        let queue = new_queue(BrandSocketOverflowPolicy::Disconnect);
+        let overflow = tokio::spawn({
+            let queue = queue.clone();
+            async move { queue.wait_for_overflow().await }
+        });
+
+        for name in ["1", "2", "3"] {
+            queue.push(property_message(name)).await;
+        }
+
+        tokio::time::timeout(Duration::from_secs(1), overflow)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(queue.get_metrics().dropped_count, 1);
+        assert_eq!(queue.try_pop().map(get_name), Some("1".to_string()));
+    }
+
+    #[tokio::test]
+    async fn queues_notifications_with_events() {
+        let queue = Arc::new(BrandSocketEventQueue::new(Default::default()));
+        let handler = BrandSocketChannelHandler::new(queue.clone());
+        let mut receiver = BrandSocketEventReceiver::new(queue.clone());
+
+        handler
+            .on_connection_state_changed(BrandSocketConnectionState::Synced)
+            .await;
+        handler.on_event_with_context(property("1"), CONTEXT).await;
+        let err = BrandSocketEventDeserializeErr::NotSupported("Unknown".to_string());
+        handler.on_undecodable_payload("{}".to_string(), err).await;
+        handler.on_stream_stale(Duration::from_secs(60)).await;
+
+        assert!(matches!(
+            receiver.try_recv(),
+            Some(BrandSocketChannelMessage::ConnectionStateChanged(
+                BrandSocketConnectionState::Synced
+            ))
+        ));
+        assert!(matches!(
+            receiver.try_recv(),
+            Some(BrandSocketChannelMessage::Event {
+                context: CONTEXT,
+                ..
+            })
+        ));
+        assert!(matches!(
+            receiver.try_recv(),
+            Some(BrandSocketChannelMessage::UndecodablePayload { payload, .. }) if payload == "{}"
+        ));
+        assert!(matches!(
+            receiver.try_recv(),
+            Some(BrandSocketChannelMessage::StreamStale(idle_for)) if idle_for.as_secs() == 60
+        ));
+        assert!(receiver.try_recv().is_none());
+    }
+}
@@ -0,0 +1,699 @@
+//! Local socket.io server imitating the Brand socket for tests. Enabled with the `mock-server` feature.
+//!
+//! Serves the engine.io handshake on `/brand-api/socket.io` (polling and websocket),
+//! accepts the `/brand-socket` namespace and replays a scripted list of `stream` events
+//! for every connection.
+
+use crate::brand_socket::models::{BrandSocketEvent, ConnectionErrorMessage, PropertyMessage};
+use futures_util::{SinkExt, StreamExt};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+const HANDSHAKE_PATH: &str = "/brand-api/socket.io";
+const NAMESPACE: &str = "/brand-socket";
+const EVENT_NAME: &str = "stream";
+const PING_INTERVAL: Duration = Duration::from_secs(25);
+
+/// A step of the script replayed after a client joins the namespace.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Clone)]
+pub enum MockBrandSocketStep {
+    /// Emits the event as a `stream` message.
+    Emit(BrandSocketEvent),
+    /// Emits the payload as is. Use it for malformed or unsupported messages.
+    EmitRaw(String),
+    Delay(Duration),
+    /// Closes the connection from the server side.
+    Disconnect,
+}
+
+impl MockBrandSocketStep {
+    pub fn sync_end() -> Self {
+        Self::Emit(BrandSocketEvent::Property(PropertyMessage {
+            name: "SyncEnd".to_string(),
+        }))
+    }
+
+    pub fn connection_error(message: &str) -> Self {
+        Self::Emit(BrandSocketEvent::ConnectionError(ConnectionErrorMessage {
+            status: Some("error".to_string()),
+            message: Some(message.to_string()),
+        }))
+    }
+}
+
+/// Serializes an event the way the Brand socket sends it: the model plus its `type`.
+pub fn serialize_event(event: &BrandSocketEvent) -> String {
+    let mut value = serde_json::to_value(event).expect("event must be serializable");
+
+    if let Some(object) = value.as_object_mut() {
+        object.insert(
+            "type".to_string(),
+            serde_json::Value::String(event.get_message_type().to_string()),
+        );
+    }
+
+    value.to_string()
+}
+
+#[derive(Default)]
+struct MockSession {
+    namespace_connected: bool,
+    pending: VecDeque<String>,
+}
+
+struct MockBrandSocketInner {
+    api_key: String,
+    /// Script of the n-th connection. The last one is reused for further connections.
+    scripts: Vec<Vec<MockBrandSocketStep>>,
+    sessions: Mutex<HashMap<String, MockSession>>,
+    connections: AtomicUsize,
+    /// Bumped by force_disconnect. Every connection watches it, so a request is not lost
+    /// while the connection is busy sending.
+    disconnect: watch::Sender<u64>,
+}
+
+pub struct MockBrandSocket {
+    inner: Arc<MockBrandSocketInner>,
+}
+
+impl MockBrandSocket {
+    /// Clients must send `api_key` in the brand-api-key header.
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            inner: Arc::new(MockBrandSocketInner {
+                api_key: api_key.into(),
+                scripts: vec![],
+                sessions: Default::default(),
+                connections: AtomicUsize::new(0),
+                disconnect: watch::channel(0).0,
+            }),
+        }
+    }
+
+    /// Adds the script for the next connection. The last added script is replayed
+    /// for all connections after it.
+    pub fn with_connection_script(mut self, script: Vec<MockBrandSocketStep>) -> Self {
+        Arc::get_mut(&mut self.inner)
+            .expect("scripts must be set before serve")
+            .scripts
+            .push(script);
+        self
+    }
+
+    /// Starts the server on a random local port.
+    pub async fn serve(self) -> std::io::Result<MockBrandSocketServer> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let address = listener.local_addr()?;
+        let inner = self.inner;
+        let server_inner = Arc::clone(&inner);
+
+        let task = tokio::spawn(async move {
+            loop {
+                let Ok((stream, _)) = listener.accept().await else {
+                    // e.g. too many open files, waits for connections to close instead of spinning
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                };
+
+                let inner = Arc::clone(&server_inner);
+                tokio::spawn(async move {
+                    _ = serve_connection(inner, stream).await;
+                });
+            }
+        });
+
+        Ok(MockBrandSocketServer {
+            url: format!("ws://{}", address),
+            inner,
+            task,
+        })
+    }
+}
+
+/// Running mock socket server. Stops when dropped.
+pub struct MockBrandSocketServer {
+    url: String,
+    inner: Arc<MockBrandSocketInner>,
+    task: JoinHandle<()>,
+}
+
+impl MockBrandSocketServer {
+    /// Url to use as BrandSocketApiConfig server url, e.g. ws://127.0.0.1:53412
+    pub fn get_url(&self) -> &str {
+        &self.url
+    }
+
+    /// Number of websocket connections which joined the namespace so far.
+    pub fn get_connections_count(&self) -> usize {
+        self.inner.connections.load(Ordering::SeqCst)
+    }
+
+    /// Closes all currently open websocket connections.
+    pub fn force_disconnect(&self) {
+        self.inner
+            .disconnect
+            .send_modify(|generation| *generation += 1);
+    }
+}
+
+impl Drop for MockBrandSocketServer {
+    fn drop(&mut self) {
+        self.task.abort();
+        self.inner
+            .disconnect
+            .send_modify(|generation| *generation += 1);
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    url: String,
+    headers: Vec<(String, String)>,
+    head_len: usize,
+}
+
+impl HttpRequest {
+    fn get_header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header_name, _)| header_name.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn get_query_param(&self, name: &str) -> Option<&str> {
+        let (_, query) = self.url.split_once('?')?;
+
+        query.split('&').find_map(|pair| {
+            let (key, value) = pair.split_once('=')?;
+            (key == name).then_some(value)
+        })
+    }
+
+    fn is_websocket(&self) -> bool {
+        self.get_header("Upgrade")
+            .is_some_and(|value| value.eq_ignore_ascii_case("websocket"))
+    }
+}
+
+/// Reads the request head without consuming it, so the websocket handshake can parse it again.
+async fn peek_request(stream: &TcpStream) -> std::io::Result<Option<HttpRequest>> {
+    let mut buffer = vec![0u8; 8192];
+
+    loop {
+        let read = stream.peek(&mut buffer).await?;
+
+        if read == 0 {
+            return Ok(None);
+        }
+
+        let Some(head_end) = buffer[..read].windows(4).position(|w| w == b"\r\n\r\n") else {
+            if read == buffer.len() {
+                return Ok(None);
+            }
+
+            tokio::time::sleep(Duration::from_millis(5)).await;
+            continue;
+        };
+
+        let head = String::from_utf8_lossy(&buffer[..head_end]).to_string();
+        let mut lines = head.split("\r\n");
+        let mut request_line = lines.next().unwrap_or_default().split(' ');
+        let method = request_line.next().unwrap_or_default().to_string();
+        let url = request_line.next().unwrap_or_default().to_string();
+        let headers = lines
+            .filter_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                Some((name.trim().to_string(), value.trim().to_string()))
+            })
+            .collect();
+
+        return Ok(Some(HttpRequest {
+            method,
+            url,
+            headers,
+            head_len: head_end + 4,
+        }));
+    }
+}
+
+async fn serve_connection(
+    inner: Arc<MockBrandSocketInner>,
+    mut stream: TcpStream,
+) -> std::io::Result<()> {
+    let Some(request) = peek_request(&stream).await? else {
+        return Ok(());
+    };
+
+    if !request.url.starts_with(HANDSHAKE_PATH) {
+        return write_http(&mut stream, 404, "Not Found").await;
+    }
+
+    if request.get_header("brand-api-key") != Some(inner.api_key.as_str()) {
+        return write_http(&mut stream, 401, "Unauthorized").await;
+    }
+
+    if request.is_websocket() {
+        let sid = request.get_query_param("sid").map(str::to_string);
+        let Ok(ws) = tokio_tungstenite::accept_async(stream).await else {
+            return Ok(());
+        };
+
+        serve_websocket(inner, ws, sid).await;
+        return Ok(());
+    }
+
+    let mut body = vec![0u8; request.head_len];
+    stream.read_exact(&mut body).await?;
+    let content_length: usize = request
+        .get_header("Content-Length")
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0u8; content_length];
+    stream.read_exact(&mut body).await?;
+
+    let response = handle_polling(&inner, &request, &String::from_utf8_lossy(&body));
+    write_http(&mut stream, 200, &response).await
+}
+
+/// Engine.io long polling: handshake, packets from the client and packets for the client.
+fn handle_polling(inner: &MockBrandSocketInner, request: &HttpRequest, body: &str) -> String {
+    let Some(sid) = request.get_query_param("sid") else {
+        let sid = uuid::Uuid::new_v4().to_string();
+        inner
+            .sessions
+            .lock()
+            .unwrap()
+            .insert(sid.clone(), MockSession::default());
+
+        return get_open_packet(&sid);
+    };
+
+    let mut sessions = inner.sessions.lock().unwrap();
+    let session = sessions.entry(sid.to_string()).or_default();
+
+    if request.method == "POST" {
+        for packet in body.split('\x1e') {
+            if is_namespace_connect(packet) {
+                session.namespace_connected = true;
+                session.pending.push_back(get_namespace_connect_ack(sid));
+            }
+        }
+
+        return "ok".to_string();
+    }
+
+    if session.pending.is_empty() {
+        return "6".to_string();
+    }
+
+    session.pending.drain(..).collect::<Vec<_>>().join("\x1e")
+}
+
+async fn serve_websocket(
+    inner: Arc<MockBrandSocketInner>,
+    mut ws: WebSocketStream<TcpStream>,
+    sid: Option<String>,
+) {
+    let mut disconnect = inner.disconnect.subscribe();
+    let sid = match sid {
+        Some(sid) => sid,
+        None => {
+            let sid = uuid::Uuid::new_v4().to_string();
+
+            if ws.send(Message::text(get_open_packet(&sid))).await.is_err() {
+                return;
+            }
+
+            sid
+        }
+    };
+
+    let (out_sender, mut out_receiver) = mpsc::unbounded_channel::<Option<String>>();
+    let mut script_task: Option<JoinHandle<()>> = None;
+    let mut ping = tokio::time::interval(PING_INTERVAL);
+    ping.tick().await;
+
+    loop {
+        tokio::select! {
+            message = ws.next() => {
+                let Some(Ok(message)) = message else {
+                    break;
+                };
+
+                let Ok(text) = message.to_text() else {
+                    continue;
+                };
+
+                let mut replies = vec![];
+
+                if text == "2probe" {
+                    replies.push("3probe".to_string());
+                } else if text == "2" {
+                    replies.push("3".to_string());
+                } else if text == "5" || is_namespace_connect(text) {
+                    let session = inner.sessions.lock().unwrap().remove(&sid);
+                    let namespace_connected = is_namespace_connect(text)
+                        || session.as_ref().is_some_and(|session| session.namespace_connected);
+
+                    if is_namespace_connect(text) {
+                        replies.push(get_namespace_connect_ack(&sid));
+                    }
+
+                    if let Some(session) = session {
+                        replies.extend(session.pending);
+                    }
+
+                    if namespace_connected && script_task.is_none() {
+                        script_task = Some(start_script(&inner, out_sender.clone()));
+                    }
+                } else if text.starts_with(&format!("41{}", NAMESPACE)) {
+                    break;
+                }
+
+                for reply in replies {
+                    if ws.send(Message::text(reply)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            packet = out_receiver.recv() => {
+                let Some(Some(packet)) = packet else {
+                    break;
+                };
+
+                if ws.send(Message::text(packet)).await.is_err() {
+                    break;
+                }
+            }
+            _ = ping.tick() => {
+                if ws.send(Message::text("2")).await.is_err() {
+                    break;
+                }
+            }
+            _ = disconnect.changed() => {
+                break;
+            }
+        }
+    }
+
+    if let Some(script_task) = script_task {
+        script_task.abort();
+    }
+
+    _ = ws.close(None).await;
+}
+
+/// Sends scripted packets to the connection, None closes it.
+fn start_script(
+    inner: &MockBrandSocketInner,
+    out_sender: mpsc::UnboundedSender<Option<String>>,
+) -> JoinHandle<()> {
+    let index = inner.connections.fetch_add(1, Ordering::SeqCst);
+    let script = inner
+        .scripts
+        .get(index)
+        .or(inner.scripts.last())
+        .cloned()
+        .unwrap_or_default();
+
+    tokio::spawn(async move {
+        for step in script {
+            let packet = match step {
+                MockBrandSocketStep::Emit(event) => {
+                    Some(get_event_packet(&serialize_event(&event)))
+                }
+                MockBrandSocketStep::EmitRaw(payload) => Some(get_event_packet(&payload)),
+                MockBrandSocketStep::Delay(delay) => {
+                    tokio::time::sleep(delay).await;
+                    continue;
+                }
+                MockBrandSocketStep::Disconnect => None,
+            };
+            let is_disconnect = packet.is_none();
+
+            if out_sender.send(packet).is_err() || is_disconnect {
+                return;
+            }
+        }
+    })
+}
+
+fn is_namespace_connect(packet: &str) -> bool {
+    packet.starts_with(&format!("40{}", NAMESPACE))
+}
+
+fn get_open_packet(sid: &str) -> String {
+    let open = serde_json::json!({
+        "sid": sid,
+        "upgrades": ["websocket"],
+        "pingInterval": PING_INTERVAL.as_millis() as u64,
+        "pingTimeout": 20000,
+        "maxPayload": 1000000,
+    });
+
+    format!("0{}", open)
+}
+
+fn get_namespace_connect_ack(sid: &str) -> String {
+    format!("40{},{}", NAMESPACE, serde_json::json!({ "sid": sid }))
+}
+
+fn get_event_packet(payload: &str) -> String {
+    format!("42{},[\"{}\",{}]", NAMESPACE, EVENT_NAME, payload)
+}
+
+async fn write_http(stream: &mut TcpStream, status_code: u16, body: &str) -> std::io::Result<()> {
+    let reason = http::StatusCode::from_u16(status_code)
+        .ok()
+        .and_then(|status| status.canonical_reason())
+        .unwrap_or_default();
+    let response = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: text/plain; charset=UTF-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status_code,
+        reason,
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand_socket::api_client::BrandSocketApiClient;
+    use crate::brand_socket::models::AccountStatusMessage;
+    use crate::brand_socket::session::BrandSocketEventPhase;
+    use crate::brand_socket::supervisor::{BrandSocketConnectionState, BrandSocketReconnectPolicy};
+    use crate::brand_socket::test_support::{TestConfig, TestHandler, TestLogger};
+    use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+    use tokio_tungstenite::MaybeTlsStream;
+
+    type Client = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+    async fn connect(server: &MockBrandSocketServer, api_key: &str) -> Option<Client> {
+        let url = format!(
+            "{}{}/?EIO=4&transport=websocket&type=LIVE",
+            server.get_url(),
+            HANDSHAKE_PATH
+        );
+        let mut request = url.into_client_request().unwrap();
+        request
+            .headers_mut()
+            .insert("brand-api-key", api_key.parse().unwrap());
+        let (mut client, _) = tokio_tungstenite::connect_async(request).await.ok()?;
+
+        let open = receive(&mut client).await;
+        assert!(open.starts_with("0{"));
+        client
+            .send(Message::text(format!("40{},", NAMESPACE)))
+            .await
+            .unwrap();
+        let ack = receive(&mut client).await;
+        assert!(ack.starts_with("40/brand-socket,{\"sid\""));
+
+        Some(client)
+    }
+
+    async fn receive(client: &mut Client) -> String {
+        let message = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+
+        message.to_text().unwrap().to_string()
+    }
+
+    fn get_account_status() -> BrandSocketEvent {
+        BrandSocketEvent::AccountStatus(AccountStatusMessage {
+            account_id: "L#1".into(),
+            currency: "USD".to_string(),
+            balance: Some("100".parse().unwrap()),
+            margin_available: None,
+            margin_used: None,
+            blocked_balance: None,
+            credit: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn replays_script() {
+        let server = MockBrandSocket::new("key")
+            .with_connection_script(vec![
+                MockBrandSocketStep::Emit(get_account_status()),
+                MockBrandSocketStep::Delay(Duration::from_millis(10)),
+                MockBrandSocketStep::sync_end(),
+                MockBrandSocketStep::Disconnect,
+            ])
+            .serve()
+            .await
+            .unwrap();
+        let mut client = connect(&server, "key").await.unwrap();
+
+        let account_status = receive(&mut client).await;
+        assert!(account_status.starts_with("42/brand-socket,[\"stream\",{"));
+        assert!(account_status.contains("\"type\":\"AccountStatus\""));
+
+        let sync_end = receive(&mut client).await;
+        assert!(sync_end.contains("\"name\":\"SyncEnd\""));
+
+        let close = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .unwrap();
+        assert!(matches!(close, Some(Ok(Message::Close(_))) | None));
+        assert_eq!(server.get_connections_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn uses_script_per_connection() {
+        let server = MockBrandSocket::new("key")
+            .with_connection_script(vec![MockBrandSocketStep::EmitRaw("{}".to_string())])
+            .with_connection_script(vec![MockBrandSocketStep::sync_end()])
+            .serve()
+            .await
+            .unwrap();
+
+        let mut first = connect(&server, "key").await.unwrap();
+        assert!(receive(&mut first).await.ends_with(",{}]"));
+
+        for _ in 0..2 {
+            let mut client = connect(&server, "key").await.unwrap();
+            assert!(receive(&mut client).await.contains("SyncEnd"));
+        }
+    }
+
+    #[tokio::test]
+    async fn forces_disconnect() {
+        let server = MockBrandSocket::new("key").serve().await.unwrap();
+        let mut client = connect(&server, "key").await.unwrap();
+
+        server.force_disconnect();
+
+        let close = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .unwrap();
+        assert!(matches!(close, Some(Ok(Message::Close(_))) | None));
+    }
+
+    #[tokio::test]
+    async fn reconnects_client_after_forced_disconnect() {
+        let server = MockBrandSocket::new("key")
+            .with_connection_script(vec![
+                MockBrandSocketStep::Emit(get_account_status()),
+                MockBrandSocketStep::sync_end(),
+            ])
+            .serve()
+            .await
+            .unwrap();
+        let handler = Arc::new(TestHandler::default());
+        let client = BrandSocketApiClient::new(
+            handler.clone(),
+            Arc::new(TestConfig::new(server.get_url())),
+            Arc::new(TestLogger),
+        )
+        .with_reconnect_policy(BrandSocketReconnectPolicy {
+            initial_backoff: Duration::from_millis(1),
+            jitter: false,
+            ..Default::default()
+        });
+
+        client.connect().await.unwrap();
+        client
+            .wait_until_sync_ended(Duration::from_secs(5))
+            .await
+            .unwrap();
+        let session = client.get_session().unwrap();
+        assert_eq!((session.id, session.snapshot_events_count), (1, 1));
+
+        server.force_disconnect();
+        let resynced = async {
+            while !client
+                .get_session()
+                .is_some_and(|session| session.id == 2 && session.is_synced())
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), resynced)
+            .await
+            .unwrap();
+
+        assert_eq!(server.get_connections_count(), 2);
+        assert!(handler
+            .get_states()
+            .contains(&BrandSocketConnectionState::Reconnecting {
+                attempt: 1,
+                delay: Duration::from_millis(1),
+            }));
+        let phases: Vec<_> = handler
+            .get_contexts()
+            .iter()
+            .map(|context| (context.session_id, context.phase))
+            .collect();
+        assert_eq!(
+            phases,
+            vec![
+                (1, BrandSocketEventPhase::Snapshot),
+                (1, BrandSocketEventPhase::Snapshot),
+                (2, BrandSocketEventPhase::Snapshot),
+                (2, BrandSocketEventPhase::Snapshot),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_api_key() {
+        let server = MockBrandSocket::new("key").serve().await.unwrap();
+
+        assert!(connect(&server, "another-key").await.is_none());
+    }
+
+    #[tokio::test]
+    async fn handshakes_with_polling() {
+        let server = MockBrandSocket::new("key").serve().await.unwrap();
+        let address = server.get_url().trim_start_matches("ws://").to_string();
+        let mut stream = TcpStream::connect(&address).await.unwrap();
+        let request = format!(
+            "GET {}/?EIO=4&transport=polling HTTP/1.1\r\nbrand-api-key: key\r\n\r\n",
+            HANDSHAKE_PATH
+        );
+
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("0{\"maxPayload\""));
+    }
+}
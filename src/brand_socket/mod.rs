@@ -1,3 +1,11 @@
 pub mod callback;
+pub mod channel;
 pub mod api_client;
-pub mod models;
\ No newline at end of file
+#[cfg(any(test, feature = "mock-server"))]
+pub mod mock_server;
+pub mod models;
+pub mod session;
+pub mod supervisor;
+#[cfg(test)]
+mod test_support;
+pub mod watchdog;
@@ -1,3 +1,4 @@
+use crate::models::{AccountId, Amount, OrderId, PositionId};
 use chrono::{DateTime, Utc};
 use my_socket_io_client::SocketIoSubscribeEventModel;
 use serde_derive::{Deserialize, Serialize};
@@ -31,9 +32,28 @@ pub struct BrandSocketEventDeserialized {
     pub result: Result<BrandSocketEvent, BrandSocketEventDeserializeErr>,
 }
 
+#[derive(Debug)]
 pub enum BrandSocketEventDeserializeErr {
     NotSupported(String),
     Serde(serde_json::Error),
+    /// The payload is not a JSON object with a string `type`.
+    InvalidType(serde_json::Error),
+}
+
+impl std::fmt::Display for BrandSocketEventDeserializeErr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BrandSocketEventDeserializeErr::NotSupported(err) => {
+                write!(f, "Not supported event: {}", err)
+            }
+            BrandSocketEventDeserializeErr::Serde(err) => {
+                write!(f, "Failed to deserialize: {}", err)
+            }
+            BrandSocketEventDeserializeErr::InvalidType(err) => {
+                write!(f, "Missing or invalid type: {}", err)
+            }
+        }
+    }
 }
 
 impl SocketIoSubscribeEventModel for BrandSocketEventDeserialized {
@@ -42,7 +62,15 @@ impl SocketIoSubscribeEventModel for BrandSocketEventDeserialized {
     const EVENT_NAME: &'static str = "stream";
 
     fn deserialize(payload: &str) -> Self {
-        let type_model: StreamTypeModel = serde_json::from_str(payload).unwrap();
+        let type_model: StreamTypeModel = match serde_json::from_str(payload) {
+            Ok(type_model) => type_model,
+            Err(err) => {
+                return BrandSocketEventDeserialized {
+                    result: Err(BrandSocketEventDeserializeErr::InvalidType(err)),
+                    payload: payload.to_string(),
+                }
+            }
+        };
 
         let result = match type_model.r#type.as_str() {
             id if id == AccountStatusMessage::get_message_type() => {
@@ -110,16 +138,16 @@ impl BrandSocketEvent {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct AccountStatusMessage {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     pub currency: String,
-    pub balance: Option<String>,
+    pub balance: Option<Amount>,
     #[serde(rename = "marginAvailable")]
-    pub margin_available: Option<String>,
+    pub margin_available: Option<Amount>,
     #[serde(rename = "marginUsed")]
-    pub margin_used: Option<String>,
+    pub margin_used: Option<Amount>,
     #[serde(rename = "blockedBalance")]
-    pub blocked_balance: Option<String>,
-    pub credit: Option<String>,
+    pub blocked_balance: Option<Amount>,
+    pub credit: Option<Amount>,
 }
 
 impl AccountStatusMessage {
@@ -145,9 +173,9 @@ impl PropertyMessage {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PositionMessage {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     #[serde(rename = "positionId")]
-    pub position_id: String,
+    pub position_id: PositionId,
     /// Number of lots in the position.
     pub lots: String,
     /// Size of each lot.
@@ -161,21 +189,21 @@ pub struct PositionMessage {
     #[serde(rename = "openDateTime")]
     pub open_date_time: DateTime<Utc>,
     #[serde(rename = "openOrderId")]
-    pub open_order_id: Option<String>, // it is required by the docs but do not sending
+    pub open_order_id: Option<OrderId>, // it is required by the docs but do not sending
     #[serde(rename = "stopLossOrderId")]
-    pub stop_loss_order_id: Option<String>,
+    pub stop_loss_order_id: Option<OrderId>,
     #[serde(rename = "stopLossLimit")]
     pub stop_loss_limit: Option<String>,
     /// Maintenance margin required for the position.
     #[serde(rename = "maintMargin")]
-    pub maint_margin: String,
+    pub maint_margin: Amount,
     #[serde(rename = "takeProfitOrderId")]
-    pub take_profit_order_id: Option<String>,
+    pub take_profit_order_id: Option<OrderId>,
     #[serde(rename = "takeProfitLimit")]
     pub take_profit_limit: Option<String>,
     pub side: PositionSide,
-    pub fee: Option<String>,
-    pub swaps: Option<String>,
+    pub fee: Option<Amount>,
+    pub swaps: Option<Amount>,
 }
 
 impl PositionMessage {
@@ -197,7 +225,7 @@ pub enum PositionSide {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ClosePositionMessage {
     #[serde(rename = "positionId")]
-    pub positions_id: String,
+    pub positions_id: PositionId,
     #[serde(rename = "closePrice")]
     pub close_price: Option<String>,
     #[serde(rename = "closeDateTime")]
@@ -213,9 +241,9 @@ impl ClosePositionMessage {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct OpenOrderMessage {
     #[serde(rename = "accountId")]
-    pub account_id: String,
+    pub account_id: AccountId,
     #[serde(rename = "orderId")]
-    pub order_id: String,
+    pub order_id: OrderId,
     pub instrument: String,
     pub amount: String,
     #[serde(rename = "lotSize")]
@@ -245,3 +273,41 @@ impl ConnectionErrorMessage {
         "ConnectionErrorMessage"
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deserializes_malformed_payloads_without_panic() {
+        for payload in ["not json", "{}", r#"{"type":1}"#, "[]"] {
+            let event = BrandSocketEventDeserialized::deserialize(payload);
+
+            assert!(
+                matches!(
+                    event.result,
+                    Err(BrandSocketEventDeserializeErr::InvalidType(_))
+                ),
+                "{}",
+                payload
+            );
+            assert_eq!(event.payload, payload);
+        }
+
+        let event = BrandSocketEventDeserialized::deserialize(r#"{"type":"Unknown"}"#);
+        assert!(matches!(
+            event.result,
+            Err(BrandSocketEventDeserializeErr::NotSupported(_))
+        ));
+
+        let event = BrandSocketEventDeserialized::deserialize(r#"{"type":"Property"}"#);
+        assert!(matches!(
+            event.result,
+            Err(BrandSocketEventDeserializeErr::Serde(_))
+        ));
+
+        let event =
+            BrandSocketEventDeserialized::deserialize(r#"{"type":"Property","name":"SyncEnd"}"#);
+        assert!(matches!(event.result, Ok(BrandSocketEvent::Property(_))));
+    }
+}
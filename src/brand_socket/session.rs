@@ -0,0 +1,132 @@
+//! Tracking of the initial snapshot of every brand socket connection.
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+/// A single connection. After connecting the server replays the current state as a snapshot
+/// terminated by the SyncEnd property message, then sends live updates.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrandSocketSession {
+    /// Number of the connection starting from 1, grows with every reconnection.
+    pub id: u64,
+    /// When the connection was established and the snapshot started.
+    pub sync_started_at: DateTime<Utc>,
+    /// When SyncEnd was received, None while the snapshot is being received.
+    pub sync_ended_at: Option<DateTime<Utc>>,
+    /// Events received before SyncEnd, not counting SyncEnd itself.
+    pub snapshot_events_count: u64,
+}
+
+impl BrandSocketSession {
+    pub fn new(id: u64) -> Self {
+        Self {
+            id,
+            sync_started_at: Utc::now(),
+            sync_ended_at: None,
+            snapshot_events_count: 0,
+        }
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.sync_ended_at.is_some()
+    }
+
+    /// Time the snapshot took, None until it ended.
+    pub fn get_sync_duration(&self) -> Option<TimeDelta> {
+        self.sync_ended_at
+            .map(|ended_at| ended_at - self.sync_started_at)
+    }
+}
+
+#[derive(strum::Display, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrandSocketEventPhase {
+    /// Part of the snapshot replayed after connecting, including SyncEnd.
+    Snapshot,
+    /// Update sent after SyncEnd.
+    Live,
+}
+
+/// Passed to BrandSocketApiEventHandler::on_event_with_context with every event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BrandSocketEventContext {
+    pub session_id: u64,
+    pub phase: BrandSocketEventPhase,
+}
+
+impl BrandSocketEventContext {
+    pub fn is_snapshot(&self) -> bool {
+        self.phase == BrandSocketEventPhase::Snapshot
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand_socket::callback::BrandSocketApiInner;
+    use crate::brand_socket::mock_server::{serialize_event, MockBrandSocketStep};
+    use crate::brand_socket::models::BrandSocketEventDeserialized;
+    use crate::brand_socket::test_support::{TestHandler, TestLogger};
+    use my_socket_io_client::{SocketIoEventSubscriberCallback, SocketIoSubscribeEventModel};
+    use std::sync::Arc;
+
+    async fn emit(inner: &BrandSocketApiInner, step: MockBrandSocketStep) {
+        let MockBrandSocketStep::Emit(event) = step else {
+            panic!("only Emit steps are supported");
+        };
+        let payload = serialize_event(&event);
+        inner
+            .on_event(BrandSocketEventDeserialized::deserialize(&payload))
+            .await;
+    }
+
+    #[test]
+    fn measures_sync_duration() {
+        let mut session = BrandSocketSession::new(1);
+        assert!(!session.is_synced());
+        assert_eq!(session.get_sync_duration(), None);
+
+        session.sync_ended_at = Some(session.sync_started_at + TimeDelta::seconds(2));
+        assert!(session.is_synced());
+        assert_eq!(session.get_sync_duration(), Some(TimeDelta::seconds(2)));
+    }
+
+    #[tokio::test]
+    async fn tags_events_and_starts_new_session_after_reset() {
+        let handler = Arc::new(TestHandler::default());
+        let inner = BrandSocketApiInner::new(handler.clone(), Arc::new(TestLogger));
+
+        emit(&inner, MockBrandSocketStep::connection_error("snapshot")).await;
+        emit(&inner, MockBrandSocketStep::connection_error("snapshot")).await;
+        emit(&inner, MockBrandSocketStep::sync_end()).await;
+        emit(&inner, MockBrandSocketStep::connection_error("live")).await;
+
+        let session = inner.get_session().unwrap();
+        assert_eq!(session.id, 1);
+        assert_eq!(session.snapshot_events_count, 2);
+        assert!(session.is_synced());
+
+        inner.reset_sync();
+        assert_eq!(inner.get_session(), None);
+        emit(&inner, MockBrandSocketStep::connection_error("snapshot")).await;
+
+        let session = inner.get_session().unwrap();
+        assert_eq!(session.id, 2);
+        assert_eq!(session.snapshot_events_count, 1);
+        assert!(!session.is_synced());
+
+        let phases: Vec<_> = handler
+            .get_contexts()
+            .iter()
+            .map(|context| (context.session_id, context.phase))
+            .collect();
+        assert_eq!(
+            phases,
+            vec![
+                (1, BrandSocketEventPhase::Snapshot),
+                (1, BrandSocketEventPhase::Snapshot),
+                (1, BrandSocketEventPhase::Snapshot),
+                (1, BrandSocketEventPhase::Live),
+                (2, BrandSocketEventPhase::Snapshot),
+            ]
+        );
+    }
+}
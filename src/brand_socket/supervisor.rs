@@ -0,0 +1,148 @@
+//! Automatic reconnection of BrandSocketApiClient, see BrandSocketApiClient::with_reconnect_policy.
+
+use crate::utils::get_random_f64;
+use std::time::Duration;
+
+/// Connection state reported to BrandSocketApiEventHandler::on_connection_state_changed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BrandSocketConnectionState {
+    /// Before connect, after disconnect or when the connection is lost.
+    Disconnected,
+    /// The socket is started and waits for the server.
+    Connecting,
+    /// Connected, the initial snapshot is being received.
+    Connected,
+    /// SyncEnd was received, only updates follow.
+    Synced,
+    /// Waits `delay` before the reconnection `attempt` starting from 1.
+    Reconnecting { attempt: u32, delay: Duration },
+    /// The reconnect policy is exhausted after `attempts` reconnections. Call connect to start again.
+    GaveUp { attempts: u32 },
+}
+
+/// Controls how BrandSocketApiClient reconnects after the connection is lost
+/// or a new connection does not receive SyncEnd in time.
+#[derive(Debug, Clone)]
+pub struct BrandSocketReconnectPolicy {
+    /// Failed reconnections in a row before giving up. None reconnects forever.
+    pub max_attempts: Option<u32>,
+    /// Delay before the first reconnection.
+    pub initial_backoff: Duration,
+    /// Upper bound for a single delay.
+    pub max_backoff: Duration,
+    /// Factor the delay grows with after each failed reconnection.
+    pub backoff_multiplier: f64,
+    /// Randomizes each delay within [delay / 2, delay].
+    pub jitter: bool,
+    /// Time a connection has to receive SyncEnd before it is considered failed.
+    pub sync_timeout: Duration,
+}
+
+impl Default for BrandSocketReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: None,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            backoff_multiplier: 2.0,
+            jitter: true,
+            sync_timeout: Duration::from_secs(15),
+        }
+    }
+}
+
+impl BrandSocketReconnectPolicy {
+    /// `attempt` is the number of the upcoming reconnection starting from 1.
+    pub fn should_reconnect(&self, attempt: u32) -> bool {
+        self.max_attempts
+            .is_none_or(|max_attempts| attempt <= max_attempts)
+    }
+
+    /// Delay before the reconnection `attempt` starting from 1.
+    pub fn get_backoff(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32) as i32;
+        let backoff = self.initial_backoff.as_secs_f64() * self.backoff_multiplier.powi(exponent);
+        let backoff = backoff.min(self.max_backoff.as_secs_f64());
+
+        if self.jitter {
+            Duration::from_secs_f64(backoff / 2.0 + backoff / 2.0 * get_random_f64())
+        } else {
+            Duration::from_secs_f64(backoff)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand_socket::api_client::BrandSocketApiClient;
+    use crate::brand_socket::test_support::{TestConfig, TestHandler, TestLogger};
+    use std::sync::Arc;
+
+    #[test]
+    fn gives_up_after_max_attempts() {
+        let policy = BrandSocketReconnectPolicy {
+            max_attempts: Some(2),
+            ..Default::default()
+        };
+
+        assert!(policy.should_reconnect(2));
+        assert!(!policy.should_reconnect(3));
+        assert!(BrandSocketReconnectPolicy::default().should_reconnect(u32::MAX));
+    }
+
+    #[test]
+    fn grows_backoff_exponentially() {
+        let policy = BrandSocketReconnectPolicy {
+            jitter: false,
+            ..Default::default()
+        };
+
+        assert_eq!(policy.get_backoff(1), Duration::from_millis(500));
+        assert_eq!(policy.get_backoff(3), Duration::from_secs(2));
+        assert_eq!(policy.get_backoff(20), Duration::from_secs(30));
+
+        let backoff = BrandSocketReconnectPolicy::default().get_backoff(2);
+        assert!(backoff >= Duration::from_millis(500) && backoff <= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn reconnects_until_policy_gives_up() {
+        let handler = Arc::new(TestHandler::default());
+        let client = BrandSocketApiClient::new(
+            handler.clone(),
+            Arc::new(TestConfig::unreachable()),
+            Arc::new(TestLogger),
+        )
+        .with_reconnect_policy(BrandSocketReconnectPolicy {
+            max_attempts: Some(1),
+            initial_backoff: Duration::from_millis(1),
+            jitter: false,
+            sync_timeout: Duration::from_millis(10),
+            ..Default::default()
+        });
+        let mut connection_state = client.subscribe_connection_state();
+
+        client.connect().await.unwrap();
+        let gave_up = connection_state
+            .wait_for(|state| matches!(state, BrandSocketConnectionState::GaveUp { .. }));
+        tokio::time::timeout(Duration::from_secs(5), gave_up)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let states = handler.get_states();
+        assert_eq!(
+            states.first(),
+            Some(&BrandSocketConnectionState::Connecting)
+        );
+        assert!(states.contains(&BrandSocketConnectionState::Reconnecting {
+            attempt: 1,
+            delay: Duration::from_millis(1),
+        }));
+        assert_eq!(
+            states.last(),
+            Some(&BrandSocketConnectionState::GaveUp { attempts: 1 })
+        );
+    }
+}
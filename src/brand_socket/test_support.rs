@@ -0,0 +1,103 @@
+//! Fixtures shared by the tests of BrandSocketApiClient.
+
+use crate::brand_socket::api_client::BrandSocketApiConfig;
+use crate::brand_socket::callback::BrandSocketApiEventHandler;
+use crate::brand_socket::models::{BrandSocketEvent, BrandSocketEventDeserializeErr};
+use crate::brand_socket::session::BrandSocketEventContext;
+use crate::brand_socket::supervisor::BrandSocketConnectionState;
+use crate::models::AccountType;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub struct TestConfig {
+    pub server_url: String,
+}
+
+impl TestConfig {
+    pub fn new(server_url: impl Into<String>) -> Self {
+        Self {
+            server_url: server_url.into(),
+        }
+    }
+
+    /// Nothing listens on the port, so connections never sync.
+    pub fn unreachable() -> Self {
+        Self::new("ws://127.0.0.1:9")
+    }
+}
+
+#[async_trait::async_trait]
+impl BrandSocketApiConfig for TestConfig {
+    async fn get_server_url(&self) -> String {
+        self.server_url.clone()
+    }
+
+    async fn get_api_key(&self) -> String {
+        "key".to_string()
+    }
+
+    async fn get_account_type(&self) -> AccountType {
+        AccountType::Live
+    }
+}
+
+/// Records everything it is called with.
+#[derive(Default)]
+pub struct TestHandler {
+    pub events: Mutex<Vec<(BrandSocketEvent, BrandSocketEventContext)>>,
+    pub states: Mutex<Vec<BrandSocketConnectionState>>,
+    pub undecodable_payloads: Mutex<Vec<String>>,
+    pub stale_periods: Mutex<Vec<Duration>>,
+}
+
+impl TestHandler {
+    pub fn get_contexts(&self) -> Vec<BrandSocketEventContext> {
+        let events = self.events.lock().unwrap();
+
+        events.iter().map(|(_, context)| *context).collect()
+    }
+
+    pub fn get_states(&self) -> Vec<BrandSocketConnectionState> {
+        self.states.lock().unwrap().clone()
+    }
+}
+
+#[async_trait::async_trait]
+impl BrandSocketApiEventHandler for TestHandler {
+    async fn on_event(&self, _event: BrandSocketEvent) {}
+
+    async fn on_event_with_context(
+        &self,
+        event: BrandSocketEvent,
+        context: BrandSocketEventContext,
+    ) {
+        self.events.lock().unwrap().push((event, context));
+    }
+
+    async fn on_connected(&self) {}
+
+    async fn on_disconnected(&self) {}
+
+    async fn on_connection_state_changed(&self, state: BrandSocketConnectionState) {
+        self.states.lock().unwrap().push(state);
+    }
+
+    async fn on_undecodable_payload(&self, payload: String, _err: BrandSocketEventDeserializeErr) {
+        self.undecodable_payloads.lock().unwrap().push(payload);
+    }
+
+    async fn on_stream_stale(&self, idle_for: Duration) {
+        self.stale_periods.lock().unwrap().push(idle_for);
+    }
+}
+
+pub struct TestLogger;
+
+impl rust_extensions::Logger for TestLogger {
+    fn write_info(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+    fn write_warning(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+    fn write_error(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+    fn write_fatal_error(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+    fn write_debug_info(&self, _: String, _: String, _: Option<HashMap<String, String>>) {}
+}
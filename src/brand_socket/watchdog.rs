@@ -0,0 +1,221 @@
+//! Detection of a connection that stays open but stops delivering events,
+//! see BrandSocketApiClient::with_watchdog.
+
+use crate::brand_socket::session::BrandSocketSession;
+use crate::brand_socket::supervisor::BrandSocketConnectionState;
+use chrono::{DateTime, Datelike, NaiveTime, TimeDelta, Timelike, Utc, Weekday};
+use std::time::Duration;
+
+/// Weekly window in UTC when events are expected, e.g. when the markets are open.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrandSocketTradingHours {
+    pub opens_at: (Weekday, NaiveTime),
+    pub closes_at: (Weekday, NaiveTime),
+}
+
+impl BrandSocketTradingHours {
+    pub fn new(opens_at: (Weekday, NaiveTime), closes_at: (Weekday, NaiveTime)) -> Self {
+        Self {
+            opens_at,
+            closes_at,
+        }
+    }
+
+    /// Sunday 22:00 to Friday 22:00 UTC.
+    pub fn forex() -> Self {
+        let time = NaiveTime::from_hms_opt(22, 0, 0).unwrap();
+
+        Self::new((Weekday::Sun, time), (Weekday::Fri, time))
+    }
+
+    pub fn contains(&self, date: DateTime<Utc>) -> bool {
+        let now = get_second_of_week(date.weekday(), date.time());
+        let opens_at = get_second_of_week(self.opens_at.0, self.opens_at.1);
+        let closes_at = get_second_of_week(self.closes_at.0, self.closes_at.1);
+
+        if opens_at <= closes_at {
+            opens_at <= now && now < closes_at
+        } else {
+            now >= opens_at || now < closes_at
+        }
+    }
+
+    /// Last time the hours opened at or before `date`.
+    pub fn get_opened_at(&self, date: DateTime<Utc>) -> DateTime<Utc> {
+        let now = get_second_of_week(date.weekday(), date.time());
+        let opens_at = get_second_of_week(self.opens_at.0, self.opens_at.1);
+        let since_open = (now + SECONDS_PER_WEEK - opens_at) % SECONDS_PER_WEEK;
+        let date = date.with_nanosecond(0).unwrap_or(date);
+
+        date - TimeDelta::seconds(since_open.into())
+    }
+}
+
+const SECONDS_PER_WEEK: u32 = 7 * 24 * 60 * 60;
+
+fn get_second_of_week(weekday: Weekday, time: NaiveTime) -> u32 {
+    weekday.num_days_from_monday() * 24 * 60 * 60 + time.num_seconds_from_midnight()
+}
+
+#[derive(Debug, Clone)]
+pub struct BrandSocketWatchdogConfig {
+    /// Time without events after which the stream is considered stale.
+    pub stale_timeout: Duration,
+    /// How often the stream is checked.
+    pub check_interval: Duration,
+    /// Closes a stale connection and connects again. With a reconnect policy the reconnection
+    /// follows the policy. Without one, reconnections in a row back off like the default policy
+    /// until a live event is received.
+    pub force_reconnect: bool,
+    /// The stream is only considered stale inside these hours, and the idle time does not start
+    /// before they open. None checks it at any time.
+    pub trading_hours: Option<BrandSocketTradingHours>,
+}
+
+impl Default for BrandSocketWatchdogConfig {
+    fn default() -> Self {
+        Self {
+            stale_timeout: Duration::from_secs(120),
+            check_interval: Duration::from_secs(5),
+            force_reconnect: false,
+            trading_hours: None,
+        }
+    }
+}
+
+impl BrandSocketWatchdogConfig {
+    pub fn is_stale(&self, idle_for: Duration, now: DateTime<Utc>) -> bool {
+        idle_for >= self.stale_timeout
+            && self
+                .trading_hours
+                .as_ref()
+                .is_none_or(|trading_hours| trading_hours.contains(now))
+    }
+}
+
+/// Liveness of BrandSocketApiClient, see BrandSocketApiClient::get_health.
+#[derive(Debug, Clone)]
+pub struct BrandSocketHealth {
+    pub connection_state: BrandSocketConnectionState,
+    pub session: Option<BrandSocketSession>,
+    pub last_event_at: Option<DateTime<Utc>>,
+    /// Time since the last event of the current session, its start or the opening of the trading
+    /// hours, whichever is later. None while disconnected.
+    pub idle_for: Option<Duration>,
+    /// Idle longer than the watchdog timeout during trading hours. Always false without a watchdog.
+    pub is_stale: bool,
+}
+
+impl BrandSocketHealth {
+    pub fn is_healthy(&self) -> bool {
+        self.connection_state == BrandSocketConnectionState::Synced && !self.is_stale
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::brand_socket::api_client::BrandSocketApiClient;
+    use crate::brand_socket::mock_server::{MockBrandSocket, MockBrandSocketStep};
+    use crate::brand_socket::test_support::{TestConfig, TestHandler, TestLogger};
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    #[test]
+    fn contains_weekly_trading_hours() {
+        let trading_hours = BrandSocketTradingHours::forex();
+
+        // 2024-06-07 is a Friday
+        let friday = |hour| Utc.with_ymd_and_hms(2024, 6, 7, hour, 0, 0).unwrap();
+        assert!(trading_hours.contains(friday(21)));
+        assert!(!trading_hours.contains(friday(22)));
+        assert!(!trading_hours.contains(Utc.with_ymd_and_hms(2024, 6, 8, 12, 0, 0).unwrap()));
+        assert!(trading_hours.contains(Utc.with_ymd_and_hms(2024, 6, 9, 23, 0, 0).unwrap()));
+        assert!(trading_hours.contains(Utc.with_ymd_and_hms(2024, 6, 10, 12, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn gets_last_opening_of_trading_hours() {
+        let trading_hours = BrandSocketTradingHours::forex();
+        // 2024-06-09 is a Sunday
+        let opened_at = Utc.with_ymd_and_hms(2024, 6, 9, 22, 0, 0).unwrap();
+
+        assert_eq!(trading_hours.get_opened_at(opened_at), opened_at);
+        let monday = Utc.with_ymd_and_hms(2024, 6, 10, 12, 30, 15).unwrap();
+        assert_eq!(trading_hours.get_opened_at(monday), opened_at);
+        let saturday = Utc.with_ymd_and_hms(2024, 6, 15, 12, 0, 0).unwrap();
+        assert_eq!(trading_hours.get_opened_at(saturday), opened_at);
+        let sunday = Utc.with_ymd_and_hms(2024, 6, 9, 21, 0, 0).unwrap();
+        assert_eq!(
+            trading_hours.get_opened_at(sunday),
+            opened_at - TimeDelta::weeks(1)
+        );
+    }
+
+    #[test]
+    fn is_stale_only_during_trading_hours() {
+        let config = BrandSocketWatchdogConfig {
+            stale_timeout: Duration::from_secs(60),
+            trading_hours: Some(BrandSocketTradingHours::forex()),
+            ..Default::default()
+        };
+        let monday = Utc.with_ymd_and_hms(2024, 6, 10, 12, 0, 0).unwrap();
+        let saturday = Utc.with_ymd_and_hms(2024, 6, 8, 12, 0, 0).unwrap();
+
+        assert!(config.is_stale(Duration::from_secs(60), monday));
+        assert!(!config.is_stale(Duration::from_secs(59), monday));
+        assert!(!config.is_stale(Duration::from_secs(600), saturday));
+    }
+
+    #[tokio::test]
+    async fn reconnects_stale_stream_without_supervisor() {
+        let server = MockBrandSocket::new("key")
+            .with_connection_script(vec![MockBrandSocketStep::sync_end()])
+            .serve()
+            .await
+            .unwrap();
+        let handler = Arc::new(TestHandler::default());
+        let client = BrandSocketApiClient::new(
+            handler.clone(),
+            Arc::new(TestConfig::new(server.get_url())),
+            Arc::new(TestLogger),
+        )
+        .with_watchdog(BrandSocketWatchdogConfig {
+            stale_timeout: Duration::from_millis(500),
+            check_interval: Duration::from_millis(10),
+            force_reconnect: true,
+            trading_hours: None,
+        });
+
+        client.connect().await.unwrap();
+        client
+            .wait_until_sync_ended(Duration::from_secs(5))
+            .await
+            .unwrap();
+        let health = client.get_health();
+        assert!(health.is_healthy(), "{:?}", health);
+        assert!(health
+            .idle_for
+            .is_some_and(|idle_for| idle_for < Duration::from_millis(500)));
+
+        let reconnected = async {
+            while !client
+                .get_session()
+                .is_some_and(|session| session.id >= 2 && session.is_synced())
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        };
+        tokio::time::timeout(Duration::from_secs(5), reconnected)
+            .await
+            .unwrap();
+
+        assert!(server.get_connections_count() >= 2);
+        assert!(!handler.stale_periods.lock().unwrap().is_empty());
+        assert!(handler.get_states().iter().any(|state| matches!(
+            state,
+            BrandSocketConnectionState::Reconnecting { attempt: 1, delay }
+                if *delay >= Duration::from_millis(250)
+        )));
+    }
+}
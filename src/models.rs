@@ -1,5 +1,311 @@
 use serde_derive::{Deserialize, Serialize};
 
+/// Monetary value: balances, pnl, fees and operation amounts.
+///
+/// Holds only numbers: json strings and numbers are checked when read, and the value is written
+/// back as a decimal string like the api sends it. With the `decimal` feature it is an exact
+/// [`rust_decimal::Decimal`] read from the exact text of json numbers, see [`Amount::to_decimal`].
+/// Without it the text is kept as is and compared by value, so "1.0" equals "1".
+#[cfg(feature = "decimal")]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Amount(rust_decimal::Decimal);
+
+/// Monetary value: balances, pnl, fees and operation amounts.
+///
+/// Holds only numbers: json strings and numbers are checked when read, and the value is written
+/// back as a decimal string like the api sends it. With the `decimal` feature it is an exact
+/// [`rust_decimal::Decimal`] read from the exact text of json numbers, see [`Amount::to_decimal`].
+/// Without it the text is kept as is and compared by value, so "1.0" equals "1".
+#[cfg(not(feature = "decimal"))]
+#[derive(Debug, Clone, Serialize)]
+#[serde(transparent)]
+pub struct Amount(String);
+
+/// Text that is not a decimal number, see [`Amount`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseAmountError(String);
+
+impl std::fmt::Display for ParseAmountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "amount is not a number: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseAmountError {}
+
+#[cfg(feature = "decimal")]
+impl Amount {
+    pub fn to_decimal(&self) -> rust_decimal::Decimal {
+        self.0
+    }
+
+    /// Lossy value, e.g. for display or rough comparisons.
+    pub fn to_f64(&self) -> f64 {
+        use rust_decimal::prelude::ToPrimitive;
+
+        self.0.to_f64().unwrap_or_default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.0.is_sign_negative() && !self.0.is_zero()
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<rust_decimal::Decimal> for Amount {
+    fn from(amount: rust_decimal::Decimal) -> Self {
+        Self(amount)
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl From<Amount> for rust_decimal::Decimal {
+    fn from(amount: Amount) -> Self {
+        amount.0
+    }
+}
+
+#[cfg(feature = "decimal")]
+impl std::str::FromStr for Amount {
+    type Err = ParseAmountError;
+
+    fn from_str(amount: &str) -> Result<Self, Self::Err> {
+        amount
+            .parse()
+            .map(Self)
+            .map_err(|_| ParseAmountError(amount.to_string()))
+    }
+}
+
+#[cfg(not(feature = "decimal"))]
+impl Amount {
+    /// Lossy value, e.g. for display or rough comparisons.
+    pub fn to_f64(&self) -> f64 {
+        self.0.parse().unwrap_or_default()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.get_normalized() == "0"
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.get_normalized().starts_with('-')
+    }
+
+    /// Same text for the same value: no plus sign, leading or trailing zeros, or negative zero.
+    fn get_normalized(&self) -> String {
+        let (is_negative, digits) = match self.0.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, self.0.strip_prefix('+').unwrap_or(&self.0)),
+        };
+        let (int, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        let int = int.trim_start_matches('0');
+        let fraction = fraction.trim_end_matches('0');
+        let mut normalized = String::with_capacity(digits.len() + 1);
+
+        if is_negative && !(int.is_empty() && fraction.is_empty()) {
+            normalized.push('-');
+        }
+
+        normalized.push_str(if int.is_empty() { "0" } else { int });
+
+        if !fraction.is_empty() {
+            normalized.push('.');
+            normalized.push_str(fraction);
+        }
+
+        normalized
+    }
+}
+
+/// Plain decimal text: an optional sign, digits and optional fraction digits.
+#[cfg(not(feature = "decimal"))]
+fn is_decimal(amount: &str) -> bool {
+    let digits = amount
+        .strip_prefix('-')
+        .or_else(|| amount.strip_prefix('+'))
+        .unwrap_or(amount);
+    let (int, fraction) = match digits.split_once('.') {
+        Some((int, fraction)) => (int, Some(fraction)),
+        None => (digits, None),
+    };
+    let is_digits = |text: &str| !text.is_empty() && text.bytes().all(|b| b.is_ascii_digit());
+
+    is_digits(int) && fraction.is_none_or(is_digits)
+}
+
+#[cfg(not(feature = "decimal"))]
+impl Default for Amount {
+    fn default() -> Self {
+        Self("0".to_string())
+    }
+}
+
+#[cfg(not(feature = "decimal"))]
+impl PartialEq for Amount {
+    fn eq(&self, other: &Self) -> bool {
+        self.get_normalized() == other.get_normalized()
+    }
+}
+
+#[cfg(not(feature = "decimal"))]
+impl Eq for Amount {}
+
+#[cfg(not(feature = "decimal"))]
+impl std::hash::Hash for Amount {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.get_normalized().hash(state)
+    }
+}
+
+#[cfg(not(feature = "decimal"))]
+impl std::str::FromStr for Amount {
+    type Err = ParseAmountError;
+
+    fn from_str(amount: &str) -> Result<Self, Self::Err> {
+        if is_decimal(amount) {
+            Ok(Self(amount.to_string()))
+        } else {
+            Err(ParseAmountError(amount.to_string()))
+        }
+    }
+}
+
+#[cfg(not(feature = "decimal"))]
+impl<'de> serde::Deserialize<'de> for Amount {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AmountVisitor;
+
+        impl serde::de::Visitor<'_> for AmountVisitor {
+            type Value = Amount;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a decimal string or number")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, amount: &str) -> Result<Amount, E> {
+                amount.parse().map_err(E::custom)
+            }
+
+            fn visit_i64<E: serde::de::Error>(self, amount: i64) -> Result<Amount, E> {
+                Ok(Amount(amount.to_string()))
+            }
+
+            fn visit_u64<E: serde::de::Error>(self, amount: u64) -> Result<Amount, E> {
+                Ok(Amount(amount.to_string()))
+            }
+
+            // shortest text of the number, exact for any number with up to 15 digits
+            fn visit_f64<E: serde::de::Error>(self, amount: f64) -> Result<Amount, E> {
+                amount.to_string().parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_any(AmountVisitor)
+    }
+}
+
+impl std::fmt::Display for Amount {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.0, f)
+    }
+}
+
+/// Compares by value, false if the text is not a number.
+impl PartialEq<&str> for Amount {
+    fn eq(&self, other: &&str) -> bool {
+        other.parse::<Amount>().is_ok_and(|other| *self == other)
+    }
+}
+
+/// Declares a serde-transparent string identifier, so ids of different entities can't be mixed up.
+macro_rules! string_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            pub fn new(id: impl Into<String>) -> Self {
+                Self(id.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn into_inner(self) -> String {
+                self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl From<String> for $name {
+            fn from(id: String) -> Self {
+                Self(id)
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(id: &str) -> Self {
+                Self(id.to_string())
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+    };
+}
+
+string_id!(
+    /// Trading account id, e.g. L#700001 for live and D#700001 for demo accounts.
+    AccountId
+);
+string_id!(UserId);
+string_id!(PositionId);
+string_id!(OrderId);
+string_id!(
+    /// Id of the group of accounts, also called user group.
+    GroupId
+);
+string_id!(TradeId);
+string_id!(
+    /// Id of a deposit, withdrawal or credit operation.
+    OperationId
+);
+
 #[derive(strum::Display, Debug, Clone, Serialize, Deserialize)]
 pub enum AccountType {
     #[strum(to_string = "DEMO")]
@@ -12,7 +318,7 @@ pub enum AccountType {
 
 #[cfg(test)]
 mod test {
-    use crate::models::AccountType;
+    use crate::models::{AccountId, AccountType};
 
     #[test]
     pub fn account_type_live() {
@@ -27,4 +333,56 @@ mod test {
 
         assert_eq!(account_type.to_string(), "DEMO".to_string());
     }
+
+    #[test]
+    pub fn id_is_transparent() {
+        let account_id: AccountId = serde_json::from_str(r#""L#1""#).unwrap();
+
+        assert_eq!(account_id, "L#1");
+        assert_eq!(serde_json::to_string(&account_id).unwrap(), r#""L#1""#);
+    }
+
+    #[test]
+    pub fn amount_keeps_wire_format() {
+        use crate::models::Amount;
+
+        let amounts: Vec<Amount> = serde_json::from_str(r#"["100.50", 2, 0.25]"#).unwrap();
+
+        assert_eq!(amounts[0], "100.5");
+        assert_eq!(amounts[1], "2.00");
+        assert_eq!(amounts[2], "0.25");
+        assert_eq!(serde_json::to_string(&amounts[0]).unwrap(), r#""100.50""#);
+        assert_eq!("-0.0".parse::<Amount>().unwrap(), "0");
+        assert!("-1.5".parse::<Amount>().unwrap().is_negative());
+    }
+
+    #[test]
+    pub fn amount_rejects_text() {
+        use crate::models::Amount;
+
+        for amount in [r#""abc""#, r#""""#, r#""1,5""#, "true"] {
+            assert!(serde_json::from_str::<Amount>(amount).is_err(), "{}", amount);
+        }
+        assert!("12a".parse::<Amount>().is_err());
+    }
+
+    #[cfg(feature = "decimal")]
+    #[test]
+    pub fn amount_is_exact() {
+        use crate::models::Amount;
+
+        let amounts: Vec<Amount> =
+            serde_json::from_str(r#"["0.1", 0.2, 12345678901234567.89, "100.50"]"#).unwrap();
+
+        assert_eq!(
+            Amount::from(amounts[0].to_decimal() + amounts[1].to_decimal()),
+            "0.3"
+        );
+        assert_eq!(amounts[2].to_string(), "12345678901234567.89");
+        assert_eq!(
+            serde_json::to_string(&amounts[2]).unwrap(),
+            r#""12345678901234567.89""#
+        );
+        assert_eq!(serde_json::to_string(&amounts[3]).unwrap(), r#""100.50""#);
+    }
 }
\ No newline at end of file
@@ -1,3 +1,19 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
 pub fn generate_password_hash(src: &str) -> String {
     format!("{:x}", md5::compute(src.as_bytes()))
 }
+
+/// Pseudo-random number in [0, 1). Good enough for jitter, must not be used for secrets.
+pub fn get_random_f64() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or_default();
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(nanos);
+
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}